@@ -9,14 +9,17 @@ struct State {}
 
 /// Event handled by the event loop.
 enum Event {
-    Input(Input),
+    Input(human::Event),
 }
 
 impl State {
     /// Event loop.
     fn event(&mut self, event: Event) {
         match event {
-            Event::Input(input) => log!(INFO, "Input: {:?}", input),
+            Event::Input(event) => {
+                let (time, input) = (event.time(), event.input());
+                log!(INFO, "Input at {:?}: {:?}", time, input)
+            }
         }
     }
 }
@@ -24,7 +27,7 @@ impl State {
 /// Start the async executor.
 fn main() {
     let mut state = State {};
-    let mut input = human::Input::listener();
+    let mut input = Input::listener();
 
     exec!(state.event(wait! {
         Event::Input((&mut input).await),
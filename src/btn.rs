@@ -8,9 +8,16 @@
 // At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
 
+use std::{
+    fmt::{Debug, Display, Formatter, Result},
+    str::FromStr,
+};
+
+use crate::ParseError;
+
 /// Input keycode for a button on a mouse.
 #[repr(u8)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub enum Btn {
     /// Left or Primary click
@@ -28,3 +35,46 @@ pub enum Btn {
     /// Extra Mouse Button
     Extra = 0x06u8,
 }
+
+impl Btn {
+    /// Iterate over every button.
+    pub(crate) fn all() -> impl Iterator<Item = Btn> {
+        NAMES.iter().map(|(btn, _)| *btn)
+    }
+}
+
+impl Display for Btn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match NAMES.iter().find(|(btn, _)| btn == self) {
+            Some((_, name)) => write!(f, "{}", name),
+            None => Debug::fmt(self, f),
+        }
+    }
+}
+
+impl FromStr for Btn {
+    type Err = ParseError;
+
+    /// Parse the name of a button (as displayed), case-insensitive.
+    fn from_str(s: &str) -> std::result::Result<Self, ParseError> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        NAMES
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(s))
+            .map(|(btn, _)| *btn)
+            .ok_or_else(|| ParseError::UnknownBtn(s.to_string()))
+    }
+}
+
+/// Name of each button.
+const NAMES: &[(Btn, &str)] = &[
+    (Btn::Left, "Left"),
+    (Btn::Middle, "Middle"),
+    (Btn::Right, "Right"),
+    (Btn::Back, "Back"),
+    (Btn::Next, "Next"),
+    (Btn::Dpi, "Dpi"),
+    (Btn::Extra, "Extra"),
+];
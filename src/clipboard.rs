@@ -0,0 +1,461 @@
+// Human
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Reading and writing text on the clipboard, for copy and paste commands.
+//!
+//! Text that the user pastes is sent by the listener as
+//! [`Input::Paste`](crate::Input::Paste), so reading the clipboard is only
+//! needed for pasting some other way (like from a menu).
+//!
+//! The clipboard used depends on the platform:
+//!  - Web: the [Clipboard API], which may ask the user for permission.
+//!  - Terminal (see [`Config::terminal()`](crate::Config::terminal)): OSC 52
+//!    escape sequences, while the listener exists.  Many terminals only allow
+//!    writing the clipboard, not reading it.  The terminal's reply is read by
+//!    the listener, so it must be polled while [`read_text()`] is awaited.
+//!  - Linux: `wl-copy` and `wl-paste` on Wayland, or `xclip` or `xsel` on X11.
+//!
+//! [Clipboard API]: https://developer.mozilla.org/en-US/docs/Web/API/Clipboard_API
+
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll, Waker},
+};
+
+#[cfg(target_os = "linux")]
+use std::time::Duration;
+
+#[cfg(target_os = "linux")]
+use crate::clock::{self, Timer};
+
+/// How long to wait for the terminal to reply to a clipboard read.
+#[cfg(target_os = "linux")]
+const TERMINAL_TIMEOUT: Duration = Duration::from_secs(1);
+
+const BASE64: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Error from reading or writing the clipboard.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// There is no clipboard that can be used (no clipboard tool on Linux, or
+    /// the terminal doesn't allow reading the clipboard).
+    Unavailable,
+    /// Access to the clipboard was denied (by the web browser).
+    Denied,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Error::Unavailable => write!(f, "clipboard is unavailable"),
+            Error::Denied => write!(f, "clipboard access was denied"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Clipboard state shared between the public functions and the listener.
+struct Clipboard {
+    // Contents of the in-memory clipboard, if it's in use.
+    memory: Option<String>,
+    // True while the terminal listener exists.
+    terminal: bool,
+    // Read waiting for the terminal to reply.
+    reading: Option<Oneshot<Result<String, Error>>>,
+}
+
+static CLIPBOARD: Mutex<Clipboard> = Mutex::new(Clipboard {
+    memory: None,
+    terminal: false,
+    reading: None,
+});
+
+fn clipboard() -> MutexGuard<'static, Clipboard> {
+    CLIPBOARD.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Read text from the clipboard.  Returns an empty string if the clipboard is
+/// empty or doesn't hold text.
+///
+/// When reading from the terminal, the reply arrives through the listener, so
+/// keep polling it while waiting for this (for example, from the same event
+/// loop).  If no reply arrives within a second, this fails with
+/// [`Error::Unavailable`].
+pub async fn read_text() -> Result<String, Error> {
+    let reading = {
+        let mut clipboard = clipboard();
+        if let Some(ref memory) = clipboard.memory {
+            return Ok(memory.clone());
+        }
+        if clipboard.terminal {
+            let reading = Oneshot::new();
+            if let Some(old) = clipboard.reading.replace(reading.clone()) {
+                old.send(Err(Error::Unavailable));
+            }
+            Some(reading)
+        } else {
+            None
+        }
+    };
+    if let Some(reading) = reading {
+        // Follow the query with a request for the primary device attributes,
+        // which every terminal replies to, so that the read fails instead of
+        // waiting forever on terminals that don't reply to the query.
+        terminal_write(b"\x1b]52;c;?\x07\x1b[c");
+        return terminal_read(reading).await;
+    }
+    native::read_text().await
+}
+
+/// Write text to the clipboard.
+pub async fn write_text(text: &str) -> Result<(), Error> {
+    {
+        let mut clipboard = clipboard();
+        if let Some(ref mut memory) = clipboard.memory {
+            *memory = text.to_string();
+            return Ok(());
+        }
+        if clipboard.terminal {
+            let sequence = format!("\x1b]52;c;{}\x07", encode(text.as_bytes()));
+            terminal_write(sequence.as_bytes());
+            return Ok(());
+        }
+    }
+    native::write_text(text).await
+}
+
+/// Use an empty in-memory clipboard, private to this program, instead of the
+/// system clipboard (for tests), until the returned guard is dropped.
+pub fn use_memory() -> Memory {
+    let previous = clipboard().memory.replace(String::new());
+    Memory { previous }
+}
+
+/// Guard that keeps the in-memory clipboard in use, returned by
+/// [`use_memory()`].  When it's dropped, the clipboard that was in use before
+/// is restored.
+#[derive(Debug)]
+#[must_use = "the in-memory clipboard is only used until this is dropped"]
+pub struct Memory {
+    // In-memory clipboard contents from before, if it was already in use.
+    previous: Option<String>,
+}
+
+impl Drop for Memory {
+    fn drop(&mut self) {
+        clipboard().memory = self.previous.take();
+    }
+}
+
+/// Switch to (or back from) the terminal's clipboard.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) fn set_terminal(terminal: bool) {
+    let mut clipboard = clipboard();
+    clipboard.terminal = terminal;
+    if let Some(reading) = clipboard.reading.take() {
+        reading.send(Err(Error::Unavailable));
+    }
+}
+
+/// Finish a read from the terminal's clipboard, with the reply to the query
+/// (`None` if the terminal replied to the device attributes request instead).
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) fn terminal_reply(text: Option<String>) {
+    if let Some(reading) = clipboard().reading.take() {
+        reading.send(text.ok_or(Error::Unavailable));
+    }
+}
+
+/// Wait for the terminal to reply to a clipboard read, giving up after
+/// `TERMINAL_TIMEOUT`.
+#[cfg(target_os = "linux")]
+async fn terminal_read(
+    mut reading: Oneshot<Result<String, Error>>,
+) -> Result<String, Error> {
+    let deadline = clock::now() + TERMINAL_TIMEOUT;
+    let mut timer = Timer::new();
+    std::future::poll_fn(move |cx| {
+        if let Poll::Ready(result) = Pin::new(&mut reading).poll(cx) {
+            return Poll::Ready(result);
+        }
+        if clock::now() >= deadline {
+            // Stop waiting for the reply, unless a newer read has taken over.
+            let mut clipboard = clipboard();
+            if clipboard.reading.as_ref().is_some_and(|r| r.is(&reading)) {
+                clipboard.reading = None;
+            }
+            return Poll::Ready(Err(Error::Unavailable));
+        }
+        match timer {
+            Some(ref mut timer) => timer.wake_at(deadline, cx),
+            None => return Poll::Ready(Err(Error::Unavailable)),
+        }
+        Poll::Pending
+    })
+    .await
+}
+
+#[cfg(not(target_os = "linux"))]
+async fn terminal_read(
+    reading: Oneshot<Result<String, Error>>,
+) -> Result<String, Error> {
+    reading.await
+}
+
+#[cfg(target_os = "linux")]
+fn terminal_write(bytes: &[u8]) {
+    use std::io::{stdout, Write};
+
+    let mut stdout = stdout();
+    let _ = stdout.write_all(bytes);
+    let _ = stdout.flush();
+}
+
+#[cfg(not(target_os = "linux"))]
+fn terminal_write(_bytes: &[u8]) {}
+
+/// Encode bytes as base64.
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let word = chunk
+            .iter()
+            .enumerate()
+            .fold(0, |word, (i, &byte)| word | u32::from(byte) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(word >> (18 - 6 * i) & 0x3F) as usize].into());
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decode base64, returning `None` if it's invalid.
+pub(crate) fn decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=').as_bytes();
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut word = 0;
+        for (i, byte) in chunk.iter().enumerate() {
+            let value = BASE64.iter().position(|b| b == byte)? as u32;
+            word |= value << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            out.push((word >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// A value that's sent from a callback or another thread.
+struct Oneshot<T>(Arc<Mutex<(Option<T>, Option<Waker>)>>);
+
+impl<T> Clone for Oneshot<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Oneshot<T> {
+    fn new() -> Self {
+        Self(Arc::new(Mutex::new((None, None))))
+    }
+
+    /// Check if this is the same oneshot as `other`.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn is(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    fn send(&self, value: T) {
+        let mut shared = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        shared.0 = Some(value);
+        if let Some(waker) = shared.1.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Future for Oneshot<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut shared = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        match shared.0.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                shared.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// The Clipboard API.
+#[cfg(target_arch = "wasm32")]
+mod native {
+    use wasm_bindgen::{closure::Closure, JsValue};
+
+    use super::{Error, Oneshot};
+
+    use web_sys::js_sys::Promise;
+
+    /// Get the browser's clipboard.
+    fn clipboard() -> Result<web_sys::Clipboard, Error> {
+        Ok(web_sys::window()
+            .ok_or(Error::Unavailable)?
+            .navigator()
+            .clipboard())
+    }
+
+    /// Wait for a promise returned by the clipboard.
+    async fn wait(promise: Promise) -> Result<JsValue, Error> {
+        let result = Oneshot::new();
+        let (ok, err) = (result.clone(), result.clone());
+        let resolve: Closure<dyn FnMut(JsValue)> =
+            Closure::wrap(Box::new(move |value| ok.send(Ok(value))));
+        let reject: Closure<dyn FnMut(JsValue)> =
+            Closure::wrap(Box::new(move |_| err.send(Err(Error::Denied))));
+        let _ = promise.then2(&resolve, &reject);
+        // The promise may settle after this future is dropped.
+        resolve.forget();
+        reject.forget();
+        result.await
+    }
+
+    pub(super) async fn read_text() -> Result<String, Error> {
+        let text = wait(clipboard()?.read_text()).await?;
+        Ok(text.as_string().unwrap_or_default())
+    }
+
+    pub(super) async fn write_text(text: &str) -> Result<(), Error> {
+        let _ = wait(clipboard()?.write_text(text)).await?;
+        Ok(())
+    }
+}
+
+/// Command-line clipboard tools, run on another thread.
+#[cfg(target_os = "linux")]
+mod native {
+    use std::{
+        env,
+        io::Write,
+        process::{Command, Stdio},
+        thread,
+    };
+
+    use super::{Error, Oneshot};
+
+    /// Commands that print the clipboard.
+    const READ: &[&[&str]] = &[
+        &["wl-paste", "--no-newline"],
+        &["xclip", "-selection", "clipboard", "-out"],
+        &["xsel", "--clipboard", "--output"],
+    ];
+
+    /// Commands that set the clipboard to their input.
+    const WRITE: &[&[&str]] = &[
+        &["wl-copy"],
+        &["xclip", "-selection", "clipboard", "-in"],
+        &["xsel", "--clipboard", "--input"],
+    ];
+
+    /// Check if a program is installed.
+    fn exists(program: &str) -> bool {
+        env::var_os("PATH").is_some_and(|paths| {
+            env::split_paths(&paths).any(|dir| dir.join(program).is_file())
+        })
+    }
+
+    /// Get the first installed command for the running display server.
+    fn command(commands: &[&[&str]], stdin: Stdio) -> Option<Command> {
+        let command = commands.iter().find(|command| {
+            let display = if command[0].starts_with("wl-") {
+                "WAYLAND_DISPLAY"
+            } else {
+                "DISPLAY"
+            };
+            env::var_os(display).is_some() && exists(command[0])
+        })?;
+        let mut cmd = Command::new(command[0]);
+        let _ = cmd.args(&command[1..]).stdin(stdin).stderr(Stdio::null());
+        Some(cmd)
+    }
+
+    /// Run a function on another thread, and wait for it to finish.
+    async fn spawn<T: Send + 'static>(
+        f: impl FnOnce() -> T + Send + 'static,
+    ) -> T {
+        let result = Oneshot::new();
+        let sender = result.clone();
+        let _ = thread::spawn(move || sender.send(f()));
+        result.await
+    }
+
+    pub(super) async fn read_text() -> Result<String, Error> {
+        spawn(|| {
+            let mut command =
+                command(READ, Stdio::null()).ok_or(Error::Unavailable)?;
+            let output = command.output().map_err(|_| Error::Unavailable)?;
+            // The tools fail when the clipboard is empty.
+            if !output.status.success() {
+                return Ok(String::new());
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        })
+        .await
+    }
+
+    pub(super) async fn write_text(text: &str) -> Result<(), Error> {
+        let text = text.to_string();
+        spawn(move || {
+            let mut command =
+                command(WRITE, Stdio::piped()).ok_or(Error::Unavailable)?;
+            let mut child = command
+                .stdout(Stdio::null())
+                .spawn()
+                .map_err(|_| Error::Unavailable)?;
+            if let Some(mut stdin) = child.stdin.take() {
+                let _ = stdin.write_all(text.as_bytes());
+            }
+            match child.wait() {
+                Ok(status) if status.success() => Ok(()),
+                _ => Err(Error::Unavailable),
+            }
+        })
+        .await
+    }
+}
+
+/// No clipboard.
+#[cfg(not(any(target_arch = "wasm32", target_os = "linux")))]
+mod native {
+    use super::Error;
+
+    pub(super) async fn read_text() -> Result<String, Error> {
+        Err(Error::Unavailable)
+    }
+
+    pub(super) async fn write_text(_text: &str) -> Result<(), Error> {
+        Err(Error::Unavailable)
+    }
+}
@@ -0,0 +1,208 @@
+// Human
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! The monotonic clock that event timestamps are measured with, and timers
+//! for waking up at a time on that clock.
+
+use std::{task::Context, time::Duration};
+
+#[cfg(target_os = "linux")]
+use std::{
+    fs::File,
+    io::Read,
+    os::{
+        raw::{c_int, c_long},
+        unix::io::FromRawFd,
+    },
+};
+
+#[cfg(target_os = "linux")]
+use smelling_salts::{Device, Watcher};
+
+/// `CLOCK_MONOTONIC`, also used for evdev timestamps (see `EVIOCSCLOCKID`).
+#[cfg(target_os = "linux")]
+pub(crate) const CLOCK_MONOTONIC: c_int = 1;
+
+#[cfg(target_os = "linux")]
+const TFD_NONBLOCK: c_int = 0o0004000;
+#[cfg(target_os = "linux")]
+const TFD_CLOEXEC: c_int = 0o2000000;
+#[cfg(target_os = "linux")]
+const TFD_TIMER_ABSTIME: c_int = 1;
+
+/// `struct timespec`
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Default)]
+struct Timespec {
+    sec: c_long,
+    nsec: c_long,
+}
+
+/// `struct itimerspec`
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Default)]
+struct Itimerspec {
+    interval: Timespec,
+    value: Timespec,
+}
+
+#[cfg(target_os = "linux")]
+extern "C" {
+    fn clock_gettime(clock: c_int, time: *mut Timespec) -> c_int;
+    fn timerfd_create(clock: c_int, flags: c_int) -> c_int;
+    fn timerfd_settime(
+        fd: c_int,
+        flags: c_int,
+        new: *const Itimerspec,
+        old: *mut Itimerspec,
+    ) -> c_int;
+}
+
+/// Get the current time, in the same time base as event timestamps.
+#[cfg(target_os = "linux")]
+#[allow(unsafe_code)]
+pub(crate) fn now() -> Duration {
+    let mut time = Timespec::default();
+    let _ = unsafe { clock_gettime(CLOCK_MONOTONIC, &mut time) };
+    Duration::new(time.sec as u64, time.nsec as u32)
+}
+
+/// Get the current time, in the same time base as event timestamps
+/// (`performance.now()`).
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now() -> Duration {
+    let millis = web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or(0.0, |performance| performance.now());
+    Duration::from_secs_f64(millis / 1000.0)
+}
+
+/// Get the current time, in the same time base as event timestamps (time since
+/// this was first called).
+#[cfg(not(any(target_arch = "wasm32", target_os = "linux")))]
+pub(crate) fn now() -> Duration {
+    use std::{sync::OnceLock, time::Instant};
+
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed()
+}
+
+/// A timer for waking up the listener.
+#[cfg(target_os = "linux")]
+pub(crate) struct Timer {
+    // Async handle for the timerfd (must be dropped before `file`).
+    device: Device,
+    // The open timerfd.
+    file: File,
+}
+
+#[cfg(target_os = "linux")]
+impl Timer {
+    #[allow(unsafe_code)]
+    pub(crate) fn new() -> Option<Self> {
+        let fd = unsafe {
+            timerfd_create(CLOCK_MONOTONIC, TFD_NONBLOCK | TFD_CLOEXEC)
+        };
+        if fd == -1 {
+            return None;
+        }
+        Some(Timer {
+            device: Device::new(fd, Watcher::new().input()),
+            file: unsafe { File::from_raw_fd(fd) },
+        })
+    }
+
+    /// Wake the task at `deadline` (in the same time base as [`now()`]).
+    #[allow(unsafe_code)]
+    pub(crate) fn wake_at(&mut self, deadline: Duration, cx: &mut Context<'_>) {
+        // Clear previous expirations.
+        let _ = self.file.read(&mut [0; 8]);
+        // An all-zero time would disarm the timer instead.
+        let deadline = deadline.max(Duration::from_nanos(1));
+        let time = Itimerspec {
+            interval: Timespec::default(),
+            value: Timespec {
+                sec: deadline.as_secs() as c_long,
+                nsec: deadline.subsec_nanos() as c_long,
+            },
+        };
+        let fd = self.device.fd();
+        let _ = unsafe {
+            timerfd_settime(fd, TFD_TIMER_ABSTIME, &time, std::ptr::null_mut())
+        };
+        self.device.register_waker(cx.waker());
+    }
+}
+
+/// A timer for waking up the listener.
+#[cfg(target_arch = "wasm32")]
+pub(crate) struct Timer {
+    // Handle for the pending `setTimeout()`.
+    timeout: Option<i32>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Timer {
+    pub(crate) fn new() -> Option<Self> {
+        Some(Timer { timeout: None })
+    }
+
+    /// Wake the task at `deadline` (in the same time base as [`now()`]).
+    pub(crate) fn wake_at(&mut self, deadline: Duration, cx: &mut Context<'_>) {
+        use wasm_bindgen::{closure::Closure, JsCast};
+
+        // Replace the timeout from the previous poll, rather than adding one.
+        self.clear();
+        let millis = deadline.saturating_sub(now()).as_secs_f64() * 1000.0;
+        let waker = cx.waker().clone();
+        let wake = Closure::once_into_js(move || waker.wake());
+        let handle = web_sys::window()
+            .unwrap()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                wake.unchecked_ref(),
+                millis.ceil() as i32,
+            );
+        self.timeout = handle.ok();
+    }
+
+    /// Cancel the pending timeout, if there is one.
+    fn clear(&mut self) {
+        if let Some(handle) = self.timeout.take() {
+            web_sys::window().unwrap().clear_timeout_with_handle(handle);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Drop for Timer {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// A timer for waking up the listener (unsupported on this platform).
+#[cfg(not(any(target_arch = "wasm32", target_os = "linux")))]
+pub(crate) struct Timer;
+
+#[cfg(not(any(target_arch = "wasm32", target_os = "linux")))]
+impl Timer {
+    pub(crate) fn new() -> Option<Self> {
+        None
+    }
+
+    pub(crate) fn wake_at(
+        &mut self,
+        _deadline: Duration,
+        _cx: &mut Context<'_>,
+    ) {
+    }
+}
@@ -0,0 +1,72 @@
+// Human
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+/// Identifier for a keyboard or pointing device, unique for as long as it's
+/// connected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DeviceId(pub(crate) usize);
+
+impl DeviceId {
+    /// Create an identifier, for tagging events from devices that you read
+    /// yourself (for example, with `EvdevDecoder` on Linux).  Identifiers made
+    /// this way may be the same as ones used by the listener.
+    pub const fn new(id: usize) -> Self {
+        Self(id)
+    }
+}
+
+/// What kind of device a [`Device`] is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DeviceKind {
+    /// A keyboard (including keyboards with a built-in pointing device).
+    Keyboard,
+    /// A mouse or other relative pointing device.  Browsers report touchpads
+    /// as mice, but on Linux, touchpads aren't read.
+    Mouse,
+    /// A touchscreen.
+    Touchscreen,
+    /// A pen tablet (or the pen of a touchscreen).
+    Pen,
+}
+
+/// A keyboard or pointing device (controllers are announced separately, with
+/// [`Input::Controller`](crate::Input::Controller)).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Device {
+    id: DeviceId,
+    name: String,
+    kind: DeviceKind,
+}
+
+impl Device {
+    #[cfg_attr(
+        not(any(target_os = "linux", target_arch = "wasm32")),
+        allow(dead_code)
+    )]
+    pub(crate) fn new(id: DeviceId, name: String, kind: DeviceKind) -> Self {
+        Self { id, name, kind }
+    }
+
+    /// Get the identifier that events from this device are tagged with.
+    pub fn id(&self) -> DeviceId {
+        self.id
+    }
+
+    /// The name of the device.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// What kind of device this is.
+    pub fn kind(&self) -> DeviceKind {
+        self.kind
+    }
+}
@@ -0,0 +1,659 @@
+// Human
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Decoding of Linux evdev `input_event` records into `Input`s.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::{TryFrom, TryInto},
+    mem::size_of,
+    time::Duration,
+};
+
+use crate::{
+    Btn, DeviceId, Event, Input, Key, KeyAction, Mod, Pen, PenTool, Scroll,
+    ScrollSource, Touch,
+};
+
+// Event types
+const EV_SYN: u16 = 0x00;
+const EV_KEY: u16 = 0x01;
+const EV_REL: u16 = 0x02;
+const EV_ABS: u16 = 0x03;
+const EV_LED: u16 = 0x11;
+
+// Synchronization event codes
+const SYN_REPORT: u16 = 0x00;
+const SYN_DROPPED: u16 = 0x03;
+
+// Relative axis codes
+const REL_X: u16 = 0x00;
+const REL_Y: u16 = 0x01;
+const REL_HWHEEL: u16 = 0x06;
+const REL_WHEEL: u16 = 0x08;
+const REL_WHEEL_HI_RES: u16 = 0x0B;
+const REL_HWHEEL_HI_RES: u16 = 0x0C;
+
+// Absolute axis codes (tablets)
+const ABS_X: u16 = 0x00;
+const ABS_Y: u16 = 0x01;
+const ABS_PRESSURE: u16 = 0x18;
+const ABS_TILT_X: u16 = 0x1A;
+const ABS_TILT_Y: u16 = 0x1B;
+
+// Absolute axis codes (multi-touch protocol B)
+const ABS_MT_SLOT: u16 = 0x2F;
+const ABS_MT_POSITION_X: u16 = 0x35;
+const ABS_MT_POSITION_Y: u16 = 0x36;
+const ABS_MT_TRACKING_ID: u16 = 0x39;
+const ABS_MT_PRESSURE: u16 = 0x3A;
+
+/// Most multi-touch slots that will be tracked for a device.
+const MAX_SLOTS: usize = 64;
+
+// Tablet tool button codes
+const BTN_TOOL_PEN: u16 = 0x140;
+const BTN_TOOL_RUBBER: u16 = 0x141;
+const BTN_TOUCH: u16 = 0x14A;
+const BTN_STYLUS: u16 = 0x14B;
+
+// Modifier and lock keycodes
+const KEY_LEFTCTRL: u16 = 29;
+const KEY_LEFTSHIFT: u16 = 42;
+const KEY_RIGHTSHIFT: u16 = 54;
+const KEY_LEFTALT: u16 = 56;
+const KEY_CAPSLOCK: u16 = 58;
+const KEY_NUMLOCK: u16 = 69;
+const KEY_SCROLLLOCK: u16 = 70;
+const KEY_RIGHTCTRL: u16 = 97;
+const KEY_RIGHTALT: u16 = 100;
+const KEY_LEFTMETA: u16 = 125;
+const KEY_RIGHTMETA: u16 = 126;
+
+// LED codes (also bit positions of `EVIOCGLED`)
+const LED_NUML: u16 = 0x00;
+const LED_CAPSL: u16 = 0x01;
+const LED_SCROLLL: u16 = 0x02;
+
+// Bit flags for held modifier keys.
+const HELD_LSHIFT: u8 = 0b0000_0001;
+const HELD_RSHIFT: u8 = 0b0000_0010;
+const HELD_LCTRL: u8 = 0b0000_0100;
+const HELD_RCTRL: u8 = 0b0000_1000;
+const HELD_LALT: u8 = 0b0001_0000;
+const HELD_RALT: u8 = 0b0010_0000;
+const HELD_LMETA: u8 = 0b0100_0000;
+const HELD_RMETA: u8 = 0b1000_0000;
+
+/// Size of the virtual screen (in mouse counts) that relative mice move a
+/// pointer around in, since evdev doesn't know about any windows.
+pub(crate) const VIRTUAL_WIDTH: f32 = 1920.0;
+pub(crate) const VIRTUAL_HEIGHT: f32 = 1080.0;
+
+/// How far (in pixels) one detent of a scroll wheel shifts the viewport; three
+/// lines, using the same line height as the web backend.
+const SCROLL_DETENT: f32 = 3.0 * 16.0;
+/// Units of `REL_WHEEL_HI_RES` and `REL_HWHEEL_HI_RES` in one detent.
+const HI_RES_DETENT: f32 = 120.0;
+
+/// Size of a `struct input_event` (a `struct timeval` followed by type, code
+/// and value).
+const RECORD: usize = 2 * size_of::<usize>() + 8;
+/// Size of each field of the `struct timeval`.
+const WORD: usize = size_of::<usize>();
+
+/// Per-device decoding state.
+#[derive(Default)]
+struct Stream {
+    // Partially received record.
+    partial: Vec<u8>,
+    // True after `SYN_DROPPED`, until the next `SYN_REPORT`.
+    dropped: bool,
+    // Relative motion accumulated since the last `SYN_REPORT`.
+    rel_x: i32,
+    rel_y: i32,
+    // Scrolling accumulated since the last `SYN_REPORT`, in detents and in
+    // high-resolution units (X, Y).
+    wheel: (i32, i32),
+    wheel_hi_res: (i32, i32),
+    // True once the device has sent high-resolution scroll events.
+    hi_res: bool,
+    // Ranges of absolute axes (minimum, maximum), by code.
+    ranges: HashMap<u16, (i32, i32)>,
+    // Multi-touch slots, and the one that's being updated.
+    slots: Vec<Slot>,
+    slot: usize,
+    // Tablet pen.
+    pen: Stylus,
+}
+
+/// A multi-touch slot.
+#[derive(Default, Copy, Clone)]
+struct Slot {
+    // Tracking ID of the touch in this slot, if there is one.
+    id: Option<u32>,
+    x: i32,
+    y: i32,
+    pressure: i32,
+    // Changes since the last `SYN_REPORT`.
+    started: bool,
+    moved: bool,
+    ended: Option<Touch>,
+}
+
+/// The state of a tablet's pen.
+#[derive(Default, Copy, Clone)]
+struct Stylus {
+    // Tool that's in range, if any.
+    tool: Option<PenTool>,
+    x: i32,
+    y: i32,
+    pressure: i32,
+    tilt_x: i32,
+    tilt_y: i32,
+    contact: bool,
+    barrel: bool,
+    // Changes since the last `SYN_REPORT`.
+    changed: bool,
+    left: bool,
+}
+
+impl Stream {
+    /// Get the touch in a slot, converted from device units.
+    fn touch(&self, slot: &Slot, id: u32) -> Touch {
+        let x = self.normalize(ABS_MT_POSITION_X, slot.x);
+        let y = self.normalize(ABS_MT_POSITION_Y, slot.y);
+        let pressure = if self.ranges.contains_key(&ABS_MT_PRESSURE) {
+            self.normalize(ABS_MT_PRESSURE, slot.pressure)
+        } else {
+            0.5
+        };
+        Touch::new(id, x, y, pressure)
+    }
+
+    /// Get the state of the pen, converted from device units.
+    fn pen(&self, tool: PenTool) -> Pen {
+        let pen = &self.pen;
+        let pressure = if !pen.contact {
+            0.0
+        } else if self.ranges.contains_key(&ABS_PRESSURE) {
+            self.normalize(ABS_PRESSURE, pen.pressure)
+        } else {
+            0.5
+        };
+        // Tablets report tilt in degrees (a resolution of 57 per radian).
+        let tilt = |tilt: i32| (tilt as f32).clamp(-90.0, 90.0);
+        let x = self.normalize(ABS_X, pen.x);
+        let y = self.normalize(ABS_Y, pen.y);
+        Pen::new(tool, x, y)
+            .with_contact(pen.contact, pressure)
+            .with_tilt(tilt(pen.tilt_x), tilt(pen.tilt_y))
+            .with_barrel(pen.barrel)
+    }
+
+    /// Decode a tablet tool button record, returning false if it isn't one.
+    fn pen_key(&mut self, code: u16, pressed: bool) -> bool {
+        let pen = &mut self.pen;
+        match code {
+            BTN_TOOL_PEN | BTN_TOOL_RUBBER => {
+                let tool = if code == BTN_TOOL_PEN {
+                    PenTool::Pen
+                } else {
+                    PenTool::Eraser
+                };
+                if pressed {
+                    pen.tool = Some(tool);
+                } else if pen.tool == Some(tool) {
+                    pen.tool = None;
+                    pen.left = true;
+                }
+            }
+            BTN_TOUCH => pen.contact = pressed,
+            BTN_STYLUS => pen.barrel = pressed,
+            _ => return false,
+        }
+        pen.changed = true;
+        true
+    }
+
+    /// Decode a tablet absolute axis record, returning false if it isn't one.
+    fn pen_abs(&mut self, code: u16, value: i32) -> bool {
+        let pen = &mut self.pen;
+        match code {
+            ABS_X => pen.x = value,
+            ABS_Y => pen.y = value,
+            ABS_PRESSURE => pen.pressure = value,
+            ABS_TILT_X => pen.tilt_x = value,
+            ABS_TILT_Y => pen.tilt_y = value,
+            _ => return false,
+        }
+        pen.changed = true;
+        true
+    }
+
+    /// Convert an absolute axis value to the range 0.0 to 1.0.
+    fn normalize(&self, code: u16, value: i32) -> f32 {
+        match self.ranges.get(&code) {
+            Some(&(min, max)) if max > min => {
+                (value - min) as f32 / (max - min) as f32
+            }
+            _ => value as f32,
+        }
+    }
+}
+
+/// Decoder for byte streams read from Linux evdev devices
+/// (`/dev/input/event*`).
+///
+/// Modifier state and the pointer position are shared between all of the
+/// devices fed into one decoder, so that holding Shift on one keyboard while
+/// clicking a separate mouse produces a shift-click.
+pub struct EvdevDecoder {
+    streams: HashMap<DeviceId, Stream>,
+    held: HashMap<DeviceId, u8>,
+    locks: u8,
+    queue: VecDeque<Event>,
+    pointer: (f32, f32),
+    pointer_lock: bool,
+    time: Duration,
+    device: DeviceId,
+}
+
+impl std::fmt::Debug for EvdevDecoder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EvdevDecoder")
+            .field("devices", &self.streams.len())
+            .field("queued", &self.queue.len())
+            .finish()
+    }
+}
+
+impl Default for EvdevDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EvdevDecoder {
+    /// Create a new decoder, with the pointer in the center of the screen.
+    pub fn new() -> Self {
+        Self {
+            streams: HashMap::new(),
+            held: HashMap::new(),
+            locks: 0,
+            queue: VecDeque::new(),
+            pointer: (VIRTUAL_WIDTH * 0.5, VIRTUAL_HEIGHT * 0.5),
+            pointer_lock: false,
+            time: Duration::default(),
+            device: DeviceId::new(0),
+        }
+    }
+
+    /// Feed bytes read from device `device`.  Records may be split across
+    /// calls.  Events decoded from them are tagged with `device`.
+    pub fn push(&mut self, device: DeviceId, bytes: &[u8]) {
+        let mut stream = self.streams.remove(&device).unwrap_or_default();
+        stream.partial.extend_from_slice(bytes);
+        let complete = stream.partial.len() / RECORD * RECORD;
+        let records: Vec<u8> = stream.partial.drain(..complete).collect();
+        self.device = device;
+        for record in records.chunks_exact(RECORD) {
+            let sec = isize::from_ne_bytes(record[..WORD].try_into().unwrap());
+            let usec = isize::from_ne_bytes(
+                record[WORD..2 * WORD].try_into().unwrap(),
+            );
+            self.time = Duration::from_secs(sec.max(0) as u64)
+                + Duration::from_micros(usec.max(0) as u64);
+            let ev_type = u16::from_ne_bytes(
+                record[RECORD - 8..RECORD - 6].try_into().unwrap(),
+            );
+            let ev_code = u16::from_ne_bytes(
+                record[RECORD - 6..RECORD - 4].try_into().unwrap(),
+            );
+            let ev_value =
+                i32::from_ne_bytes(record[RECORD - 4..].try_into().unwrap());
+            self.record(device, &mut stream, ev_type, ev_code, ev_value);
+        }
+        self.streams.insert(device, stream);
+    }
+
+    /// Forget about a device that has been unplugged, releasing any modifiers
+    /// it was holding, cancelling its touches and taking its pen out of range.
+    pub fn remove(&mut self, device: DeviceId) {
+        if let Some(mut stream) = self.streams.remove(&device) {
+            self.device = device;
+            self.cancel(&mut stream);
+        }
+        let _ = self.held.remove(&device);
+    }
+
+    /// Set the range of an absolute axis of device `device` (from
+    /// `EVIOCGABS`), so that touch and pen positions and pressure can be
+    /// converted into the range 0.0 to 1.0.  Without a range, positions are in
+    /// device units and pressure is always 0.5.
+    pub fn set_abs_range(
+        &mut self,
+        device: DeviceId,
+        code: u16,
+        min: i32,
+        max: i32,
+    ) {
+        let stream = self.streams.entry(device).or_default();
+        let _ = stream.ranges.insert(code, (min, max));
+    }
+
+    /// Lock or unlock the pointer.  While it's locked, mouse motion is only
+    /// sent as [`Input::PointerDelta`], and the pointer stays in place.
+    pub fn set_pointer_lock(&mut self, locked: bool) {
+        self.pointer_lock = locked;
+    }
+
+    /// Take the next decoded event, timestamped with the time of its record
+    /// and tagged with the device it came from.
+    pub fn pop(&mut self) -> Option<Event> {
+        self.queue.pop_front()
+    }
+
+    /// Queue a decoded event, from the current record.
+    fn send(&mut self, input: Input) {
+        let event = Event::new(self.time, input).with_device(self.device);
+        self.queue.push_back(event);
+    }
+
+    /// Set the state of the lock keys from a keyboard's LEDs (bitmask of
+    /// `LED_*` codes, as returned by `EVIOCGLED`).
+    pub(crate) fn set_leds(&mut self, leds: u8) {
+        self.locks = leds & (1 << LED_NUML | 1 << LED_CAPSL | 1 << LED_SCROLLL);
+    }
+
+    /// Current modifier state, from all devices.
+    fn mods(&self) -> Mod {
+        let held = self.held.values().fold(0, |all, held| all | held);
+        let mut mods = Mod::new();
+        if held & (HELD_LSHIFT | HELD_RSHIFT) != 0 {
+            mods = mods.add_shift();
+        }
+        if held & (HELD_LCTRL | HELD_RCTRL) != 0 {
+            mods = mods.add_ctrl();
+        }
+        if held & HELD_LALT != 0 {
+            mods = mods.add_alt();
+        }
+        // The right ALT key is ALT GR on the layouts that have one.
+        if held & HELD_RALT != 0 {
+            mods = mods.add_altgr();
+        }
+        if held & (HELD_LMETA | HELD_RMETA) != 0 {
+            mods = mods.add_super();
+        }
+        if self.locks & 1 << LED_CAPSL != 0 {
+            mods = mods.add_caps_lock();
+        }
+        if self.locks & 1 << LED_NUML != 0 {
+            mods = mods.add_num_lock();
+        }
+        if self.locks & 1 << LED_SCROLLL != 0 {
+            mods = mods.add_scroll_lock();
+        }
+        mods
+    }
+
+    /// Decode one record.
+    fn record(
+        &mut self,
+        device: DeviceId,
+        stream: &mut Stream,
+        ty: u16,
+        code: u16,
+        value: i32,
+    ) {
+        if stream.dropped {
+            // Events were lost, so skip the rest of this (incomplete) frame.
+            if ty == EV_SYN && code == SYN_REPORT {
+                stream.dropped = false;
+            }
+            return;
+        }
+        match ty {
+            EV_SYN => match code {
+                SYN_REPORT => self.report(stream),
+                SYN_DROPPED => {
+                    stream.dropped = true;
+                    stream.rel_x = 0;
+                    stream.rel_y = 0;
+                    stream.wheel = (0, 0);
+                    stream.wheel_hi_res = (0, 0);
+                    // Touches and pens can't be followed after losing events.
+                    self.cancel(stream);
+                }
+                _ => {}
+            },
+            EV_KEY => {
+                let action = match value {
+                    0 => KeyAction::Release,
+                    1 => KeyAction::Press,
+                    _ => KeyAction::Repeat,
+                };
+                if stream.pen_key(code, action.is_down()) {
+                    return;
+                }
+                if let Some(btn) = button(code) {
+                    // Mouse buttons don't auto-repeat.
+                    if action != KeyAction::Repeat {
+                        let mods = self.mods();
+                        let pressed = action == KeyAction::Press;
+                        self.send(Input::Click(mods, btn, pressed));
+                    }
+                    return;
+                }
+                let flag = held(code);
+                let held = self.held.entry(device).or_default();
+                if action.is_down() {
+                    *held |= flag;
+                } else {
+                    *held &= !flag;
+                }
+                // Lock keys toggle on press (LED events, if any, correct
+                // this later).
+                if action == KeyAction::Press {
+                    self.locks ^= match code {
+                        KEY_NUMLOCK => 1 << LED_NUML,
+                        KEY_CAPSLOCK => 1 << LED_CAPSL,
+                        KEY_SCROLLLOCK => 1 << LED_SCROLLL,
+                        _ => 0,
+                    };
+                }
+                if let Some(key) = Key::from_evdev(code) {
+                    let mods = self.mods();
+                    self.send(Input::Key(mods, key, action));
+                }
+            }
+            EV_REL => match code {
+                REL_X => stream.rel_x += value,
+                REL_Y => stream.rel_y += value,
+                // Wheels count up for scrolling up, the opposite of `Scroll`.
+                REL_WHEEL => stream.wheel.1 -= value,
+                REL_HWHEEL => stream.wheel.0 += value,
+                REL_WHEEL_HI_RES => {
+                    stream.hi_res = true;
+                    stream.wheel_hi_res.1 -= value;
+                }
+                REL_HWHEEL_HI_RES => {
+                    stream.hi_res = true;
+                    stream.wheel_hi_res.0 += value;
+                }
+                _ => {}
+            },
+            EV_ABS => self.abs(stream, code, value),
+            EV_LED => {
+                let bit = match code {
+                    LED_NUML | LED_CAPSL | LED_SCROLLL => 1 << code,
+                    _ => return,
+                };
+                if value != 0 {
+                    self.locks |= bit;
+                } else {
+                    self.locks &= !bit;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Decode a tablet or multi-touch (protocol B) absolute axis record.
+    fn abs(&mut self, stream: &mut Stream, code: u16, value: i32) {
+        if stream.pen_abs(code, value) {
+            return;
+        }
+        if code == ABS_MT_SLOT {
+            stream.slot = (value.max(0) as usize).min(MAX_SLOTS - 1);
+            return;
+        }
+        if stream.slots.len() <= stream.slot {
+            stream.slots.resize(stream.slot + 1, Slot::default());
+        }
+        let mut slot = stream.slots[stream.slot];
+        match code {
+            ABS_MT_TRACKING_ID => {
+                let id = u32::try_from(value).ok();
+                if let Some(old) = slot.id.filter(|&old| Some(old) != id) {
+                    // Touches that end in the frame they started aren't sent.
+                    if !slot.started {
+                        slot.ended = Some(stream.touch(&slot, old));
+                    }
+                    slot.started = false;
+                }
+                if id.is_some() && id != slot.id {
+                    slot.started = true;
+                }
+                slot.id = id;
+            }
+            ABS_MT_POSITION_X => slot.x = value,
+            ABS_MT_POSITION_Y => slot.y = value,
+            ABS_MT_PRESSURE => slot.pressure = value,
+            _ => return,
+        }
+        slot.moved = true;
+        stream.slots[stream.slot] = slot;
+    }
+
+    /// Cancel all of a device's touches, and take its pen out of range.
+    fn cancel(&mut self, stream: &mut Stream) {
+        for i in 0..stream.slots.len() {
+            let slot = stream.slots[i];
+            if let Some(id) = slot.id.filter(|_| !slot.started) {
+                self.send(Input::TouchCancel(stream.touch(&slot, id)));
+            }
+            stream.slots[i] = Slot::default();
+        }
+        if stream.pen.tool.is_some() {
+            self.send(Input::PenLeave);
+        }
+        stream.pen = Stylus::default();
+    }
+
+    /// End of a frame; send accumulated pointer, touch and pen changes.
+    fn report(&mut self, stream: &mut Stream) {
+        if stream.rel_x != 0 || stream.rel_y != 0 {
+            let (dx, dy) = (stream.rel_x as f32, stream.rel_y as f32);
+            if !self.pointer_lock {
+                self.pointer.0 =
+                    (self.pointer.0 + dx).clamp(0.0, VIRTUAL_WIDTH);
+                self.pointer.1 =
+                    (self.pointer.1 + dy).clamp(0.0, VIRTUAL_HEIGHT);
+                self.send(Input::PointerMove {
+                    x: self.pointer.0 / VIRTUAL_WIDTH,
+                    y: self.pointer.1 / VIRTUAL_HEIGHT,
+                });
+            }
+            self.send(Input::PointerDelta { dx, dy });
+            stream.rel_x = 0;
+            stream.rel_y = 0;
+        }
+        if stream.wheel != (0, 0) || stream.wheel_hi_res != (0, 0) {
+            // High-resolution wheels also send whole detents, but only once
+            // they add up.
+            let scroll = if stream.hi_res {
+                let (x, y) = stream.wheel_hi_res;
+                Scroll::new(
+                    ScrollSource::Continuous,
+                    x as f32 * SCROLL_DETENT / HI_RES_DETENT,
+                    y as f32 * SCROLL_DETENT / HI_RES_DETENT,
+                )
+            } else {
+                let (x, y) = stream.wheel;
+                Scroll::new(
+                    ScrollSource::Wheel,
+                    x as f32 * SCROLL_DETENT,
+                    y as f32 * SCROLL_DETENT,
+                )
+            };
+            let (x, y) = stream.wheel;
+            let mods = self.mods();
+            self.send(Input::Scroll(mods, scroll.with_detents(x, y)));
+            stream.wheel = (0, 0);
+            stream.wheel_hi_res = (0, 0);
+        }
+        for i in 0..stream.slots.len() {
+            let slot = stream.slots[i];
+            if let Some(touch) = slot.ended {
+                self.send(Input::TouchEnd(touch));
+            }
+            if let Some(id) = slot.id {
+                let touch = stream.touch(&slot, id);
+                if slot.started {
+                    self.send(Input::TouchStart(touch));
+                } else if slot.moved {
+                    self.send(Input::TouchMove(touch));
+                }
+            }
+            let slot = &mut stream.slots[i];
+            slot.started = false;
+            slot.moved = false;
+            slot.ended = None;
+        }
+        if stream.pen.left {
+            self.send(Input::PenLeave);
+        }
+        if let Some(tool) = stream.pen.tool.filter(|_| stream.pen.changed) {
+            self.send(Input::Pen(stream.pen(tool)));
+        }
+        stream.pen.changed = false;
+        stream.pen.left = false;
+    }
+}
+
+/// Modifier bit flag for a keycode (0 if it's not a modifier).
+fn held(code: u16) -> u8 {
+    match code {
+        KEY_LEFTSHIFT => HELD_LSHIFT,
+        KEY_RIGHTSHIFT => HELD_RSHIFT,
+        KEY_LEFTCTRL => HELD_LCTRL,
+        KEY_RIGHTCTRL => HELD_RCTRL,
+        KEY_LEFTALT => HELD_LALT,
+        KEY_RIGHTALT => HELD_RALT,
+        KEY_LEFTMETA => HELD_LMETA,
+        KEY_RIGHTMETA => HELD_RMETA,
+        _ => 0,
+    }
+}
+
+/// Converter for evdev button codes to human mouse buttons.
+fn button(code: u16) -> Option<Btn> {
+    Some(match code {
+        0x110 => Btn::Left,
+        0x111 => Btn::Right,
+        0x112 => Btn::Middle,
+        0x113 | 0x116 => Btn::Back,
+        0x114 | 0x115 => Btn::Next,
+        0x117 => Btn::Extra,
+        _ => return None,
+    })
+}
@@ -0,0 +1,320 @@
+// Human
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use std::{collections::VecDeque, f32::consts::PI, time::Duration};
+
+use crate::{DeviceId, Event, Input, Touch};
+
+/// Longest a touch can be held down and still be a tap.
+const TAP_TIME: Duration = Duration::from_millis(300);
+/// How long a touch must be held still to be a long press.
+const LONG_PRESS_TIME: Duration = Duration::from_millis(500);
+/// How far a touch can move and still count as held still (as a fraction of
+/// the surface).
+const SLOP: f32 = 0.02;
+/// Shortest distance a swipe can travel (as a fraction of the surface).
+const SWIPE_DISTANCE: f32 = 0.1;
+/// Slowest a swipe can travel, in fractions of the surface per second.
+const SWIPE_SPEED: f32 = 0.5;
+
+/// Direction of a [`Gesture::Swipe`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Towards the left edge.
+    Left,
+    /// Towards the right edge.
+    Right,
+    /// Towards the top edge.
+    Up,
+    /// Towards the bottom edge.
+    Down,
+}
+
+/// A gesture, recognized by a [`GestureRecognizer`].  Positions and distances
+/// are in the same coordinates as [`Touch`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Gesture {
+    /// Two fingers moved apart or together, changing the distance between them
+    /// by a factor (greater than 1.0 for zooming in).
+    Pinch(f32),
+    /// Two fingers turned, by an angle in radians (positive is clockwise).
+    Rotate(f32),
+    /// Two fingers moved together, moving the point between them by (X, Y).
+    Pan(f32, f32),
+    /// One finger was quickly moved in a direction and lifted.
+    Swipe(Direction),
+    /// One finger was quickly touched and lifted without moving, at (X, Y).
+    Tap(f32, f32),
+    /// One finger was held down without moving, at (X, Y).
+    LongPress(f32, f32),
+}
+
+/// A finger that's down.
+#[derive(Debug, Copy, Clone)]
+struct Finger {
+    device: Option<DeviceId>,
+    id: u32,
+    start: (f32, f32),
+    position: (f32, f32),
+    time: Duration,
+    // True once the finger has moved farther than `SLOP`.
+    moved: bool,
+}
+
+/// The two fingers that pinch, rotate and pan gestures are measured between.
+#[derive(Debug, Copy, Clone)]
+struct Pair {
+    distance: f32,
+    angle: f32,
+    center: (f32, f32),
+}
+
+impl Pair {
+    fn new(a: &Finger, b: &Finger) -> Self {
+        let (dx, dy) =
+            (b.position.0 - a.position.0, b.position.1 - a.position.1);
+        Self {
+            distance: dx.hypot(dy),
+            angle: dy.atan2(dx),
+            center: (
+                (a.position.0 + b.position.0) * 0.5,
+                (a.position.1 + b.position.1) * 0.5,
+            ),
+        }
+    }
+}
+
+/// Recognizer for touchscreen gestures, from the touch events of any backend.
+///
+/// Feed every [`Event`] to [`update()`](GestureRecognizer::update), then take
+/// the recognized gestures with [`pop()`](GestureRecognizer::pop).  Long
+/// presses are recognized when time passes without any events, so also call
+/// [`advance()`](GestureRecognizer::advance) when
+/// [`deadline()`](GestureRecognizer::deadline) is reached.
+///
+/// Taps, swipes and long presses are only recognized for a single finger;
+/// pinch, rotate and pan gestures are measured between the first two fingers
+/// that are down.
+///
+/// How far a finger must move to stop being a tap, or to be a swipe, is a
+/// fraction of the surface that touches are on.  When positions aren't
+/// normalized (see [`Config::coordinates()`](crate::Config::coordinates)),
+/// tell the recognizer the surface's size with
+/// [`resize()`](GestureRecognizer::resize).
+#[derive(Debug, Clone)]
+pub struct GestureRecognizer {
+    // Size of the surface, in the same units as touch positions.
+    size: (f32, f32),
+    fingers: Vec<Finger>,
+    pair: Option<Pair>,
+    // True once more than one finger has been down, until all are lifted.
+    multi: bool,
+    // When the finger becomes a long press, if it's held still.
+    long_press: Option<Duration>,
+    // True once a long press has been sent, until the finger is lifted.
+    long_pressed: bool,
+    queue: VecDeque<Gesture>,
+}
+
+impl Default for GestureRecognizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GestureRecognizer {
+    /// Create a gesture recognizer, with no fingers down, for normalized
+    /// positions (on a surface that's 1.0 by 1.0).
+    pub fn new() -> Self {
+        Self {
+            size: (1.0, 1.0),
+            fingers: Vec::new(),
+            pair: None,
+            multi: false,
+            long_press: None,
+            long_pressed: false,
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Set the width and height of the surface that touches are on, in the
+    /// same units as touch positions.
+    pub fn resize(&mut self, width: f32, height: f32) {
+        self.size = (width, height);
+    }
+
+    /// Update the recognizer from an input event (events that aren't touches
+    /// are ignored).
+    pub fn update(&mut self, event: &Event) {
+        match *event.input() {
+            Input::TouchStart(touch) => self.start(event, touch),
+            Input::TouchMove(touch) => self.motion(event, touch),
+            Input::TouchEnd(touch) => self.end(event, touch, false),
+            Input::TouchCancel(touch) => self.end(event, touch, true),
+            _ => {}
+        }
+    }
+
+    /// Get when the held finger becomes a long press, if it stays still.
+    pub fn deadline(&self) -> Option<Duration> {
+        self.long_press
+    }
+
+    /// Let time pass without any events, recognizing a long press if it's due
+    /// at time `now` (in the same time base as [`Event::time()`]).
+    pub fn advance(&mut self, now: Duration) {
+        if self.long_press.is_some_and(|deadline| now >= deadline) {
+            self.long_press = None;
+            self.long_pressed = true;
+            if let Some(finger) = self.fingers.first() {
+                let (x, y) = finger.position;
+                self.queue.push_back(Gesture::LongPress(x, y));
+            }
+        }
+    }
+
+    /// Take the next recognized gesture.
+    pub fn pop(&mut self) -> Option<Gesture> {
+        self.queue.pop_front()
+    }
+
+    fn start(&mut self, event: &Event, touch: Touch) {
+        self.advance(event.time());
+        let position = (touch.x(), touch.y());
+        self.fingers.push(Finger {
+            device: event.device(),
+            id: touch.id(),
+            start: position,
+            position,
+            time: event.time(),
+            moved: false,
+        });
+        match self.fingers.len() {
+            1 => {
+                self.multi = false;
+                self.long_pressed = false;
+                self.long_press = Some(event.time() + LONG_PRESS_TIME);
+            }
+            2 => {
+                self.multi = true;
+                self.long_press = None;
+                self.pair = Some(Pair::new(&self.fingers[0], &self.fingers[1]));
+            }
+            _ => {}
+        }
+    }
+
+    fn motion(&mut self, event: &Event, touch: Touch) {
+        self.advance(event.time());
+        let i = match self.find(event, touch) {
+            Some(i) => i,
+            None => return,
+        };
+        let finger = &mut self.fingers[i];
+        finger.position = (touch.x(), touch.y());
+        let (dx, dy) = (
+            finger.position.0 - finger.start.0,
+            finger.position.1 - finger.start.1,
+        );
+        if fraction(self.size, dx, dy) > SLOP {
+            finger.moved = true;
+            self.long_press = None;
+        }
+
+        if i >= 2 {
+            return;
+        }
+        let (old, new) = match (self.pair, self.fingers.get(1)) {
+            (Some(old), Some(other)) => {
+                (old, Pair::new(&self.fingers[0], other))
+            }
+            _ => return,
+        };
+        self.pair = Some(new);
+        if old.distance > 0.0 && new.distance != old.distance {
+            self.queue
+                .push_back(Gesture::Pinch(new.distance / old.distance));
+        }
+        if new.angle != old.angle {
+            // Take the short way around.
+            let mut angle = new.angle - old.angle;
+            if angle > PI {
+                angle -= 2.0 * PI;
+            } else if angle < -PI {
+                angle += 2.0 * PI;
+            }
+            self.queue.push_back(Gesture::Rotate(angle));
+        }
+        if new.center != old.center {
+            self.queue.push_back(Gesture::Pan(
+                new.center.0 - old.center.0,
+                new.center.1 - old.center.1,
+            ));
+        }
+    }
+
+    fn end(&mut self, event: &Event, touch: Touch, cancel: bool) {
+        self.advance(event.time());
+        let finger = match self.find(event, touch) {
+            Some(i) => self.fingers.remove(i),
+            None => return,
+        };
+        self.pair = match self.fingers.get(..2) {
+            Some([a, b]) => Some(Pair::new(a, b)),
+            _ => None,
+        };
+        if !self.fingers.is_empty() {
+            return;
+        }
+        self.long_press = None;
+        if cancel || self.multi || self.long_pressed {
+            return;
+        }
+
+        let (x, y) = (touch.x(), touch.y());
+        let (dx, dy) = (x - finger.start.0, y - finger.start.1);
+        let elapsed = event.time().saturating_sub(finger.time);
+        if !finger.moved {
+            if elapsed <= TAP_TIME {
+                self.queue.push_back(Gesture::Tap(x, y));
+            }
+            return;
+        }
+        let distance = fraction(self.size, dx, dy);
+        let seconds = elapsed.as_secs_f32().max(f32::EPSILON);
+        if distance >= SWIPE_DISTANCE && distance / seconds >= SWIPE_SPEED {
+            let direction = if dx.abs() > dy.abs() {
+                if dx > 0.0 {
+                    Direction::Right
+                } else {
+                    Direction::Left
+                }
+            } else if dy > 0.0 {
+                Direction::Down
+            } else {
+                Direction::Up
+            };
+            self.queue.push_back(Gesture::Swipe(direction));
+        }
+    }
+
+    /// Find the index of the finger for a touch.
+    fn find(&self, event: &Event, touch: Touch) -> Option<usize> {
+        self.fingers.iter().position(|finger| {
+            finger.device == event.device() && finger.id == touch.id()
+        })
+    }
+}
+
+/// Convert a distance into a fraction of a surface that's `size` big.
+fn fraction(size: (f32, f32), dx: f32, dy: f32) -> f32 {
+    (dx / size.0).hypot(dy / size.1)
+}
@@ -0,0 +1,33 @@
+// Human
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Input method (IME) support, for text that's composed from several key
+//! presses, like CJK text or accented letters typed with dead keys.
+//!
+//! While text is being composed, the listener sends
+//! [`Input::CompositionStart`](crate::Input::CompositionStart),
+//! [`Input::CompositionUpdate`](crate::Input::CompositionUpdate) and
+//! [`Input::CompositionEnd`](crate::Input::CompositionEnd) events.
+
+/// Tell the input method where the text cursor is, so that its candidate
+/// window shows up next to the text being composed instead of in a corner of
+/// the window.  The area is in pixels from the top left of the window (CSS
+/// pixels on the web, from the top left of the element for
+/// [`Input::listener_for()`](crate::Input)), and should cover the cursor, or
+/// the pre-edit text.
+///
+/// This does nothing on platforms without an input method.
+pub fn set_cursor_area(x: f32, y: f32, width: f32, height: f32) {
+    #[cfg(target_arch = "wasm32")]
+    crate::web::set_cursor_area(x, y, width, height);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = (x, y, width, height);
+}
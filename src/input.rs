@@ -9,12 +9,17 @@
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
 
 use std::{
+    ops::Range,
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use pasts::{Notifier, prelude::*};
-use crate::{Controls, Key, Mod, Btn};
+use crate::{
+    clock::{self, Timer},
+    Btn, Config, Controls, Device, DeviceId, Key, KeyAction, KeyRepeat, Mod,
+};
 
 /// A gamepad, flightstick, smartphone, or other controller.
 #[derive(Debug)]
@@ -50,25 +55,81 @@ impl Notifier for Controller {
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum Input {
-    /// User inputted text.
-    Text(char),
-    /// A key on the keyboard was pressed or released.
-    Key(Mod, Key, bool),
+    /// User inputted text (typed, or committed by an input method).  Text
+    /// that's entered at once is sent as one string, so grapheme clusters
+    /// aren't split.
+    Text(String),
+    /// User pasted text, all at once.
+    Paste(String),
+    /// An input method started composing text (see [`crate::ime`]).
+    CompositionStart,
+    /// The text being composed changed: the pre-edit text to show in place of
+    /// the cursor, and the cursor (or selection) within it as a range of
+    /// bytes.
+    CompositionUpdate(String, Range<usize>),
+    /// Composition finished, with the committed text (empty if it was
+    /// cancelled).  The committed text is also sent as [`Input::Text`]
+    /// afterwards, so programs that don't show pre-edit text can ignore
+    /// composition events.
+    CompositionEnd(String),
+    /// A key on the keyboard was pressed, auto-repeated or released.
+    Key(Mod, Key, KeyAction),
     /// Pointer button was pressed or released (Left click, Tap).
     Click(Mod, Btn, bool),
+    /// The pointer has entered the window.
+    PointerEnter,
     /// The pointer has left the window.
     PointerLeave,
-    /// Request to shift the viewport in the X dimension (relative coordinates).
-    ScrollX(Mod, f32),
-    /// Request to shift the viewport in the Y dimension (relative coordinates).
-    ScrollY(Mod, f32),
-    /// The pointer was moved in the X dimension (absolute coordinates).
-    PointerX(f32),
-    /// The pointer was moved in the Y dimension (absolute coordinates).
-    PointerY(f32),
+    /// The window (or terminal) gained keyboard focus.
+    FocusGained,
+    /// The window (or terminal) lost keyboard focus.  The listener sends
+    /// releases for any keys and buttons that are held down first, since
+    /// their real releases won't arrive.
+    FocusLost,
+    /// The window was shown (`true`) or hidden (`false`), for example when
+    /// switching browser tabs or minimizing it.
+    Visibility(bool),
+    /// Request to shift the viewport (mouse wheel or touchpad scrolling).
+    Scroll(Mod, Scroll),
+    /// The pointer was moved (absolute coordinates, in the coordinate space
+    /// chosen with [`Config::coordinates()`](crate::Config::coordinates)).
+    PointerMove {
+        /// Position from the left edge.
+        x: f32,
+        /// Position from the top edge.
+        y: f32,
+    },
+    /// The mouse was moved, by a relative amount that isn't limited by the
+    /// edges of the window (CSS pixels on the web, and mouse counts on evdev).
+    /// Sent along with [`Input::PointerMove`], or alone while the pointer is
+    /// locked (see [`crate::pointer`]).
+    PointerDelta {
+        /// Motion towards the right.
+        dx: f32,
+        /// Motion towards the bottom.
+        dy: f32,
+    },
+    /// The pointer was locked (`true`) or unlocked (`false`).
+    PointerLock(bool),
+    /// A finger (or stylus) touched the touchscreen.
+    TouchStart(Touch),
+    /// A touch moved, or its pressure changed.
+    TouchMove(Touch),
+    /// A finger was lifted from the touchscreen.
+    TouchEnd(Touch),
+    /// A touch was cancelled (for example, taken over by the operating system
+    /// for a gesture, or lost because events were dropped), rather than lifted
+    /// on purpose.
+    TouchCancel(Touch),
+    /// A pen came within range of the tablet (or touchscreen), moved, or its
+    /// pressure, tilt or buttons changed.
+    Pen(Pen),
+    /// The pen left the range of the tablet.
+    PenLeave,
     /// Touchscreen was touched with one finger.
     Touch(bool),
-    /// Touchscreen was touched with two fingers.
+    /// Touchscreen was touched with two fingers.  Pinch events aren't sent by
+    /// any backend; use a [`GestureRecognizer`](crate::GestureRecognizer).
     Pinch(bool),
     /// Pinch width has changed.
     PinchW(f32),
@@ -78,47 +139,453 @@ pub enum Input {
     PinchZ(f32),
     /// New controller plugged in.
     Controller(Controller),
+    /// New keyboard or pointing device plugged in (or already connected when
+    /// the listener was created).
+    DeviceAdded(Device),
+    /// Keyboard or pointing device unplugged.
+    DeviceRemoved(Device),
+}
+
+/// One point of contact on a touchscreen.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Touch {
+    id: u32,
+    x: f32,
+    y: f32,
+    pressure: f32,
+}
+
+impl Touch {
+    /// Create a touch point.
+    pub fn new(id: u32, x: f32, y: f32, pressure: f32) -> Self {
+        Self { id, x, y, pressure }
+    }
+
+    /// Get the identifier of the touch, which stays the same from when it
+    /// starts until it ends, and is unique among the touches that are down at
+    /// the same time on a device.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Get the X position, from 0.0 (left) to 1.0 (right) of the window (or
+    /// screen, when reading from evdev), unless another coordinate space was
+    /// chosen with [`Config::coordinates()`](crate::Config::coordinates).
+    pub fn x(&self) -> f32 {
+        self.x
+    }
+
+    /// Get the Y position, from 0.0 (top) to 1.0 (bottom) of the window (or
+    /// screen, when reading from evdev), unless another coordinate space was
+    /// chosen.
+    pub fn y(&self) -> f32 {
+        self.y
+    }
+
+    /// Get how hard the screen is being pressed, from 0.0 to 1.0 (0.5 if the
+    /// touchscreen can't sense pressure).
+    pub fn pressure(&self) -> f32 {
+        self.pressure
+    }
+
+    pub(crate) fn scaled(mut self, sx: f32, sy: f32) -> Self {
+        self.x *= sx;
+        self.y *= sy;
+        self
+    }
+}
+
+/// What kind of device a [`Scroll`] came from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ScrollSource {
+    /// A wheel that turns in steps (detents).
+    Wheel,
+    /// Fingers moving on a touchpad.
+    Touchpad,
+    /// Any other smooth source, like a high-resolution or free-spinning wheel
+    /// (or a wheel or touchpad that the browser doesn't say apart).
+    Continuous,
+}
+
+/// An amount to scroll by, both in pixels and in wheel detents.
+///
+/// Positive amounts scroll towards the right and the bottom of the content
+/// (the same as DOM `WheelEvent`s).  Pixels are CSS pixels on the web; other
+/// backends scroll by three 16-pixel lines per detent.  Sources without
+/// detents still report them, one for every three lines of scrolling that's
+/// built up, so that line-based scrolling stays consistent.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Scroll {
+    source: ScrollSource,
+    x: f32,
+    y: f32,
+    detents: (i32, i32),
+}
+
+impl Scroll {
+    /// Create a scroll by (`x`, `y`) pixels, with no detents.
+    pub fn new(source: ScrollSource, x: f32, y: f32) -> Self {
+        Self {
+            source,
+            x,
+            y,
+            detents: (0, 0),
+        }
+    }
+
+    /// Set how many detents were scrolled horizontally and vertically.
+    pub fn with_detents(mut self, x: i32, y: i32) -> Self {
+        self.detents = (x, y);
+        self
+    }
+
+    /// Get what kind of device the scroll came from.
+    pub fn source(&self) -> ScrollSource {
+        self.source
+    }
+
+    /// Get the precise amount to scroll by, in pixels (X, Y).
+    pub fn delta(&self) -> (f32, f32) {
+        (self.x, self.y)
+    }
+
+    /// Get the number of whole detents to scroll by (X, Y).
+    pub fn detents(&self) -> (i32, i32) {
+        self.detents
+    }
+
+    pub(crate) fn merge(&mut self, other: &Self) {
+        self.x += other.x;
+        self.y += other.y;
+        self.detents.0 += other.detents.0;
+        self.detents.1 += other.detents.1;
+    }
+}
+
+/// Which end of a pen is being used.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PenTool {
+    /// The tip, for drawing.
+    Pen,
+    /// The eraser, on the other end (or selected with a button).
+    Eraser,
+}
+
+/// The state of a pen (stylus) on a tablet or touchscreen.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Pen {
+    tool: PenTool,
+    x: f32,
+    y: f32,
+    pressure: f32,
+    tilt_x: f32,
+    tilt_y: f32,
+    twist: f32,
+    contact: bool,
+    barrel: bool,
+}
+
+impl Pen {
+    /// Create a pen hovering at (`x`, `y`), with no pressure, tilt, twist or
+    /// buttons held.
+    pub fn new(tool: PenTool, x: f32, y: f32) -> Self {
+        Self {
+            tool,
+            x,
+            y,
+            pressure: 0.0,
+            tilt_x: 0.0,
+            tilt_y: 0.0,
+            twist: 0.0,
+            contact: false,
+            barrel: false,
+        }
+    }
+
+    /// Set whether the pen is touching the surface, and how hard.
+    pub fn with_contact(mut self, contact: bool, pressure: f32) -> Self {
+        self.contact = contact;
+        self.pressure = pressure;
+        self
+    }
+
+    /// Set the tilt of the pen, in degrees.
+    pub fn with_tilt(mut self, tilt_x: f32, tilt_y: f32) -> Self {
+        self.tilt_x = tilt_x;
+        self.tilt_y = tilt_y;
+        self
+    }
+
+    /// Set the rotation of the pen around its own axis, in degrees.
+    pub fn with_twist(mut self, twist: f32) -> Self {
+        self.twist = twist;
+        self
+    }
+
+    /// Set whether the barrel button is held.
+    pub fn with_barrel(mut self, barrel: bool) -> Self {
+        self.barrel = barrel;
+        self
+    }
+
+    /// Get which end of the pen is being used.
+    pub fn tool(&self) -> PenTool {
+        self.tool
+    }
+
+    /// Get the X position, from 0.0 (left) to 1.0 (right) of the window (or
+    /// tablet, when reading from evdev), unless another coordinate space was
+    /// chosen with [`Config::coordinates()`](crate::Config::coordinates).
+    pub fn x(&self) -> f32 {
+        self.x
+    }
+
+    /// Get the Y position, from 0.0 (top) to 1.0 (bottom) of the window (or
+    /// tablet, when reading from evdev), unless another coordinate space was
+    /// chosen.
+    pub fn y(&self) -> f32 {
+        self.y
+    }
+
+    /// Get whether the pen is touching the surface.
+    pub fn contact(&self) -> bool {
+        self.contact
+    }
+
+    /// Get how hard the pen is pressed, from 0.0 to 1.0 (always 0.0 while
+    /// hovering, and 0.5 while touching if the tablet can't sense pressure).
+    pub fn pressure(&self) -> f32 {
+        self.pressure
+    }
+
+    /// Get the angle between the pen and the surface's normal, in degrees
+    /// from -90.0 to 90.0, tilting towards the right edge (X) and the bottom
+    /// edge (Y).
+    pub fn tilt(&self) -> (f32, f32) {
+        (self.tilt_x, self.tilt_y)
+    }
+
+    /// Get the clockwise rotation of the pen around its own axis, in degrees
+    /// from 0.0 to 360.0 (0.0 if the pen can't sense it).
+    pub fn twist(&self) -> f32 {
+        self.twist
+    }
+
+    /// Get whether the barrel button (on the side of the pen) is held.
+    pub fn barrel(&self) -> bool {
+        self.barrel
+    }
+
+    pub(crate) fn scaled(mut self, sx: f32, sy: f32) -> Self {
+        self.x *= sx;
+        self.y *= sy;
+        self
+    }
+}
+
+/// An [`Input`] event, along with when it happened.
+#[derive(Debug)]
+pub struct Event {
+    time: Duration,
+    device: Option<DeviceId>,
+    input: Input,
+}
+
+impl Event {
+    /// Create an event that happened at `time`, not from any specific device.
+    pub fn new(time: Duration, input: Input) -> Self {
+        Self {
+            time,
+            device: None,
+            input,
+        }
+    }
+
+    /// Mark the event as coming from `device`.
+    pub fn with_device(mut self, device: DeviceId) -> Self {
+        self.device = Some(device);
+        self
+    }
+
+    /// Get when the event happened, according to a monotonic clock with a
+    /// platform-specific starting point (DOM `timeStamp` on the web, and
+    /// `CLOCK_MONOTONIC` on Linux).  Timestamps are only comparable to those of
+    /// other events from the same listener.
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Get the keyboard or pointing device the event came from, if any
+    /// (events like focus changes don't come from a device).
+    pub fn device(&self) -> Option<DeviceId> {
+        self.device
+    }
+
+    /// Get the input event.
+    pub fn input(&self) -> &Input {
+        &self.input
+    }
+
+    /// Convert into the input event, dropping the timestamp.
+    pub fn into_input(self) -> Input {
+        self.input
+    }
+
+    pub(crate) fn input_mut(&mut self) -> &mut Input {
+        &mut self.input
+    }
+
+    pub(crate) fn set_time(&mut self, time: Duration) {
+        self.time = time;
+    }
 }
 
 struct InputListener<T: Future + Unpin> {
     ctlr: T,
+    #[cfg(target_os = "linux")]
+    linux: Option<crate::linux::Hub>,
+    #[cfg(target_os = "linux")]
+    tty: Option<crate::tty::Tty>,
+    // Software key repeat, if enabled.
+    repeat: Option<KeyRepeat>,
+    // Timer for waking up when the next repeat is due.
+    timer: Option<Timer>,
+}
+
+impl<T: Future + Unpin> InputListener<T> {
+    /// Poll the keyboard and mouse backend.
+    fn poll_input(&mut self, cx: &mut Context<'_>) -> Poll<Event> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            crate::web::poll(cx)
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(ref mut tty) = self.tty {
+                tty.poll(cx)
+            } else if let Some(ref mut linux) = self.linux {
+                linux.poll(cx)
+            } else {
+                Pending
+            }
+        }
+
+        #[cfg(not(any(target_arch = "wasm32", target_os = "linux")))]
+        {
+            let _ = cx;
+            Pending
+        }
+    }
 }
 
 impl<T> Notifier for InputListener<T>
 where
     T: Future<Output = (usize, Controls)> + Unpin,
 {
-    type Event = Input;
+    type Event = Event;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Event> {
         let this = self.get_mut();
-        
-        if let Poll::Ready((_, Controls::Connect(new))) =
+
+        if let Ready((_, Controls::Connect(new))) =
             Pin::new(&mut this.ctlr).poll(cx)
         {
-            return Poll::Ready(Input::Controller(Controller(new)));
+            let input = Input::Controller(Controller(new));
+            return Ready(Event::new(clock::now(), input));
         }
 
-        #[cfg(target_arch = "wasm32")]
-        {
-            crate::web::poll(cx)
+        while let Ready(event) = this.poll_input(cx) {
+            match this.repeat {
+                Some(ref mut repeat) => {
+                    if let Some(event) = repeat.filter(event) {
+                        return Ready(event);
+                    }
+                }
+                None => return Ready(event),
+            }
         }
 
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            Poll::Pending
+        if let Some(ref mut repeat) = this.repeat {
+            if let Some(event) = repeat.repeat(clock::now()) {
+                return Ready(event);
+            }
+            if let (Some(deadline), Some(timer)) =
+                (repeat.deadline(), this.timer.as_mut())
+            {
+                timer.wake_at(deadline, cx);
+            }
         }
+        Pending
     }
 }
 
 impl Input {
-    /// Get a notifier that returns input events.
-    pub fn listener() -> impl Notifier<Event = Input> {
+    /// Get a notifier that returns timestamped input events.
+    pub fn listener() -> impl Notifier<Event = Event> {
+        Self::listener_with(Config::new())
+    }
+
+    /// Get a notifier that returns timestamped input events, with custom
+    /// settings.
+    pub fn listener_with(config: Config) -> impl Notifier<Event = Event> {
         #[cfg(target_arch = "wasm32")]
-        crate::web::init();
+        crate::web::init(config, None);
+
+        Self::listen(config)
+    }
+
+    /// Get a notifier that returns timestamped input events from a DOM
+    /// element (like a canvas) instead of from the whole window (web only).
+    ///
+    /// Pointer, wheel and touch input is only received over the element (or
+    /// while dragging from it), and positions are relative to it.  Listeners
+    /// are attached to the element, so scrolling is only prevented over it.
+    /// Keyboard input is only captured while the element is active: from when
+    /// it's clicked or touched until something else on the page gets focus.
+    #[cfg(target_arch = "wasm32")]
+    pub fn listener_for(
+        element: &web_sys::HtmlElement,
+    ) -> impl Notifier<Event = Event> {
+        Self::listener_for_with(element, Config::new())
+    }
+
+    /// Get a notifier that returns timestamped input events from a DOM
+    /// element, with custom settings (web only).
+    #[cfg(target_arch = "wasm32")]
+    pub fn listener_for_with(
+        element: &web_sys::HtmlElement,
+        config: Config,
+    ) -> impl Notifier<Event = Event> {
+        crate::web::init(config, Some(element.clone()));
+
+        Self::listen(config)
+    }
+
+    fn listen(config: Config) -> impl Notifier<Event = Event> {
+        #[cfg(target_os = "linux")]
+        let (linux, tty) = if config.terminal {
+            match crate::tty::Tty::new(config) {
+                Ok(tty) => (None, Some(tty)),
+                Err(error) => {
+                    // The listener can't return an error, so say why it won't
+                    // receive anything.
+                    eprintln!("human: can't read input from stdin: {}", error);
+                    (None, None)
+                }
+            }
+        } else {
+            (Some(crate::linux::Hub::new(config)), None)
+        };
 
         InputListener {
             ctlr: stick::Controller::listener(),
+            #[cfg(target_os = "linux")]
+            linux,
+            #[cfg(target_os = "linux")]
+            tty,
+            repeat: config.repeat,
+            timer: config.repeat.and_then(|_| Timer::new()),
         }
     }
 }
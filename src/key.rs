@@ -8,22 +8,51 @@
 // At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
 
-use std::fmt::{Debug, Formatter, Result};
+use std::{
+    fmt::{Debug, Display, Formatter, Result},
+    str::FromStr,
+};
+
+use crate::ParseError;
 
 const MOD_SHIFT: u8 = 0b0000_0001;
 const MOD_CTRL: u8 = 0b0000_0010;
 const MOD_ALT: u8 = 0b0000_0100;
+const MOD_SUPER: u8 = 0b0000_1000;
+const MOD_ALTGR: u8 = 0b0001_0000;
+const MOD_CAPS_LOCK: u8 = 0b0010_0000;
+const MOD_NUM_LOCK: u8 = 0b0100_0000;
+const MOD_SCROLL_LOCK: u8 = 0b1000_0000;
+
+/// Lock keys, which are toggled rather than held down.
+const MOD_LOCKS: u8 = MOD_CAPS_LOCK | MOD_NUM_LOCK | MOD_SCROLL_LOCK;
+
+/// Names of each modifier, in the order they are displayed.
+const MOD_NAMES: &[(u8, &str)] = &[
+    (MOD_CTRL, "Ctrl"),
+    (MOD_SUPER, "Super"),
+    (MOD_ALT, "Alt"),
+    (MOD_ALTGR, "AltGr"),
+    (MOD_SHIFT, "Shift"),
+    (MOD_CAPS_LOCK, "CapsLock"),
+    (MOD_NUM_LOCK, "NumLock"),
+    (MOD_SCROLL_LOCK, "ScrollLock"),
+];
+
+/// Other names accepted when parsing modifiers.
+const MOD_ALIASES: &[(u8, &str)] = &[(MOD_SUPER, "Meta")];
 
-/// Modifier state.
+/// Modifier state: which modifier keys are held down, and which lock keys are
+/// on.
 #[repr(transparent)]
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
 pub struct Mod(u8);
 
 impl Mod {
-    /// Check if no modifiers are held down.
+    /// Check if no modifiers are held down (lock keys may be on).
     #[inline(always)]
     pub fn none(self) -> bool {
-        self.0 == 0
+        self.0 & !MOD_LOCKS == 0
     }
 
     /// Check if SHIFT is held down.
@@ -32,7 +61,7 @@ impl Mod {
         (self.0 & MOD_SHIFT) != 0
     }
 
-    /// Check if CTRL or CMD is held down.
+    /// Check if CTRL is held down.
     #[inline(always)]
     pub fn ctrl(self) -> bool {
         (self.0 & MOD_CTRL) != 0
@@ -44,9 +73,54 @@ impl Mod {
         (self.0 & MOD_ALT) != 0
     }
 
+    /// Check if SUPER (Windows, CMD or META) is held down.
+    #[inline(always)]
+    pub fn super_key(self) -> bool {
+        (self.0 & MOD_SUPER) != 0
+    }
+
+    /// Check if ALT GR is held down.
+    ///
+    /// With evdev, the right ALT key is always reported as ALT GR.  Terminals
+    /// without the kitty keyboard protocol send ALT GR as plain text, so it's
+    /// never reported there.
+    #[inline(always)]
+    pub fn altgr(self) -> bool {
+        (self.0 & MOD_ALTGR) != 0
+    }
+
+    /// Check if CAPS LOCK is on.
+    #[inline(always)]
+    pub fn caps_lock(self) -> bool {
+        (self.0 & MOD_CAPS_LOCK) != 0
+    }
+
+    /// Check if NUM LOCK is on.
+    #[inline(always)]
+    pub fn num_lock(self) -> bool {
+        (self.0 & MOD_NUM_LOCK) != 0
+    }
+
+    /// Check if SCROLL LOCK is on.
+    #[inline(always)]
+    pub fn scroll_lock(self) -> bool {
+        (self.0 & MOD_SCROLL_LOCK) != 0
+    }
+
+    /// Only the modifiers that are held down, without lock key state.
+    #[inline(always)]
+    pub fn held(self) -> Self {
+        Self(self.0 & !MOD_LOCKS)
+    }
+
+    /// Check if every modifier in `other` is also in `self`.
+    pub(crate) fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
     /// No modifiers.
     #[inline(always)]
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         Mod(0)
     }
 
@@ -67,60 +141,138 @@ impl Mod {
     pub fn add_alt(self) -> Self {
         Self(self.0 | MOD_ALT)
     }
+
+    /// Add super key.
+    #[inline(always)]
+    pub fn add_super(self) -> Self {
+        Self(self.0 | MOD_SUPER)
+    }
+
+    /// Add alt gr key.
+    #[inline(always)]
+    pub fn add_altgr(self) -> Self {
+        Self(self.0 | MOD_ALTGR)
+    }
+
+    /// Add caps lock.
+    #[inline(always)]
+    pub fn add_caps_lock(self) -> Self {
+        Self(self.0 | MOD_CAPS_LOCK)
+    }
+
+    /// Add num lock.
+    #[inline(always)]
+    pub fn add_num_lock(self) -> Self {
+        Self(self.0 | MOD_NUM_LOCK)
+    }
+
+    /// Add scroll lock.
+    #[inline(always)]
+    pub fn add_scroll_lock(self) -> Self {
+        Self(self.0 | MOD_SCROLL_LOCK)
+    }
+}
+
+impl Mod {
+    /// Write the names of each modifier, separated by `separator`.
+    fn write(self, f: &mut Formatter<'_>, separator: &str) -> Result {
+        if self.0 == 0 {
+            return write!(f, "None");
+        }
+        let mut sep = "";
+        for &(flag, name) in MOD_NAMES {
+            if self.0 & flag != 0 {
+                write!(f, "{}{}", sep, name)?;
+                sep = separator;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Debug for Mod {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        if self.ctrl() {
-            if self.alt() {
-                if self.shift() {
-                    write!(f, "Ctrl + Alt + Shift")
-                } else {
-                    write!(f, "Ctrl + Alt")
-                }
-            } else {
-                write!(f, "Ctrl")
+        self.write(f, " + ")
+    }
+}
+
+impl Display for Mod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.write(f, "+")
+    }
+}
+
+impl FromStr for Mod {
+    type Err = ParseError;
+
+    /// Parse modifiers separated by `+` (for example `"Ctrl+Shift"`), or
+    /// `"None"`.  Names are case-insensitive.
+    fn from_str(s: &str) -> std::result::Result<Self, ParseError> {
+        if s.trim().eq_ignore_ascii_case("None") {
+            return Ok(Mod::new());
+        }
+        let mut mods = Mod::new();
+        for name in s.split('+').map(str::trim) {
+            if name.is_empty() {
+                return Err(ParseError::Empty);
             }
-        } else if self.alt() {
-            if self.shift() {
-                write!(f, "Alt + Shift")
-            } else {
-                write!(f, "Alt")
+            match MOD_NAMES
+                .iter()
+                .chain(MOD_ALIASES)
+                .find(|(_, n)| n.eq_ignore_ascii_case(name))
+            {
+                Some((flag, _)) => mods.0 |= flag,
+                None => return Err(ParseError::UnknownMod(name.to_string())),
             }
-        } else if self.shift() {
-            write!(f, "Shift")
-        } else {
-            write!(f, "None")
         }
+        Ok(mods)
+    }
+}
+
+/// Whether a key was pressed, repeated or released.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum KeyAction {
+    /// The key was pressed.
+    Press,
+    /// The key is being held down, and auto-repeated.
+    Repeat,
+    /// The key was released.
+    Release,
+}
+
+impl KeyAction {
+    /// Check if the key is down (pressed or repeated).
+    pub fn is_down(self) -> bool {
+        self != KeyAction::Release
     }
 }
 
 /// Input keycode for a key on a keyboard.
 #[repr(u8)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub enum Key {
     /// The Escape Key
     Esc = 0x00u8,
-    /// Numeric 1 on either top row or numpad.
+    /// Numeric 1 on the top row.
     One = 0x01,
-    /// Numeric 2 on either top row or numpad.
+    /// Numeric 2 on the top row.
     Two = 0x02,
-    /// Numeric 3 on either top row or numpad.
+    /// Numeric 3 on the top row.
     Three = 0x03,
-    /// Numeric 4 on either top row or numpad.
+    /// Numeric 4 on the top row.
     Four = 0x04,
-    /// Numeric 5 on either top row or numpad.
+    /// Numeric 5 on the top row.
     Five = 0x05,
-    /// Numeric 6 on either top row or numpad.
+    /// Numeric 6 on the top row.
     Six = 0x06,
-    /// Numeric 7 on either top row or numpad.
+    /// Numeric 7 on the top row.
     Seven = 0x07,
-    /// Numeric 8 on either top row or numpad.
+    /// Numeric 8 on the top row.
     Eight = 0x08,
-    /// Numeric 9 on either top row or numpad.
+    /// Numeric 9 on the top row.
     Nine = 0x09,
-    /// Numeric 0 on either top row or numpad.
+    /// Numeric 0 on the top row.
     Zero = 0x0A,
     /// Minus / Underscore Key
     Minus = 0x0B,
@@ -335,6 +487,40 @@ pub enum Key {
     PageDown = 0x7E,
     /// End
     End = 0x7F,
+    /// Numpad 1
+    NumOne = 0x80,
+    /// Numpad 2
+    NumTwo = 0x81,
+    /// Numpad 3
+    NumThree = 0x82,
+    /// Numpad 4
+    NumFour = 0x83,
+    /// Numpad 5
+    NumFive = 0x84,
+    /// Numpad 6
+    NumSix = 0x85,
+    /// Numpad 7
+    NumSeven = 0x86,
+    /// Numpad 8
+    NumEight = 0x87,
+    /// Numpad 9
+    NumNine = 0x88,
+    /// Numpad 0
+    NumZero = 0x89,
+    /// Numpad Decimal Point
+    NumPeriod = 0x8A,
+    /// Numpad Divide
+    NumSlash = 0x8B,
+    /// Numpad Multiply
+    NumStar = 0x8C,
+    /// Numpad Subtract
+    NumMinus = 0x8D,
+    /// Numpad Add
+    NumPlus = 0x8E,
+    /// Numpad Equal Sign
+    NumEqual = 0x8F,
+    /// Numpad Comma (Separator)
+    NumComma = 0x90,
 
     // RESERVED: LShift = 0x6A,
     // RESERVED: RShift = 0x75,
@@ -343,3 +529,1011 @@ pub enum Key {
     // RESERVED: RAlt = 0x7B,
     // RESERVED: RCtrl = 0x7C
 }
+
+impl Key {
+    /// Iterate over every key.
+    pub(crate) fn all() -> impl Iterator<Item = Key> {
+        NAMES.iter().map(|(key, _)| *key)
+    }
+
+    /// Convert a Linux evdev keycode (`KEY_*` from
+    /// `linux/input-event-codes.h`) to a `Key`.
+    pub fn from_evdev(code: u16) -> Option<Key> {
+        EVDEV
+            .iter()
+            .find(|(_, c)| *c == code)
+            .or_else(|| EVDEV_ALIASES.iter().find(|(_, c)| *c == code))
+            .map(|(key, _)| *key)
+    }
+
+    /// Convert to a Linux evdev keycode, if there's one for this key.
+    pub fn to_evdev(self) -> Option<u16> {
+        EVDEV
+            .iter()
+            .find(|(k, _)| *k == self)
+            .map(|(_, code)| *code)
+    }
+
+    /// Convert a USB HID usage page and usage ID to a `Key`.  Keys are found
+    /// on the Keyboard/Keypad (0x07), Consumer (0x0C) and Generic Desktop
+    /// (0x01) pages.
+    pub fn from_hid_usage(page: u16, usage: u16) -> Option<Key> {
+        HID.iter()
+            .find(|(_, u)| *u == (page, usage))
+            .or_else(|| HID_ALIASES.iter().find(|(_, u)| *u == (page, usage)))
+            .map(|(key, _)| *key)
+    }
+
+    /// Convert to a USB HID usage page and usage ID, if there's one for this
+    /// key.
+    pub fn to_hid_usage(self) -> Option<(u16, u16)> {
+        HID.iter()
+            .find(|(k, _)| *k == self)
+            .map(|(_, usage)| *usage)
+    }
+
+    /// Convert an X11 keysym to a `Key`.  Both cases of a letter convert to
+    /// the same key, and keypad keysyms for when Num Lock is off convert to
+    /// the numpad key.
+    pub fn from_keysym(keysym: u32) -> Option<Key> {
+        KEYSYM
+            .iter()
+            .find(|(_, k)| *k == keysym)
+            .or_else(|| KEYSYM_ALIASES.iter().find(|(_, k)| *k == keysym))
+            .map(|(key, _)| *key)
+    }
+
+    /// Convert to an X11 keysym (unshifted), if there's one for this key.
+    pub fn to_keysym(self) -> Option<u32> {
+        KEYSYM
+            .iter()
+            .find(|(k, _)| *k == self)
+            .map(|(_, keysym)| *keysym)
+    }
+
+    /// Convert a Windows virtual-key code (`VK_*`) to a `Key`.  The side-less
+    /// `VK_SHIFT`, `VK_CONTROL` and `VK_MENU` convert to the left key.
+    pub fn from_vk(vk: u16) -> Option<Key> {
+        VK.iter()
+            .find(|(_, v)| *v == vk)
+            .or_else(|| VK_ALIASES.iter().find(|(_, v)| *v == vk))
+            .map(|(key, _)| *key)
+    }
+
+    /// Convert to a Windows virtual-key code, if there's one for this key.
+    pub fn to_vk(self) -> Option<u16> {
+        VK.iter().find(|(k, _)| *k == self).map(|(_, vk)| *vk)
+    }
+
+    /// Convert a DOM `KeyboardEvent.code` to a `Key`.
+    ///
+    /// Browsers report [`Key::Thumb`] (ALT GR) as `AltRight` and [`Key::Break`]
+    /// as `Pause`, and have no code for [`Key::Airplane`], so those three keys
+    /// are never returned.
+    pub fn from_dom_code(code: &str) -> Option<Key> {
+        DOM.iter()
+            .find(|(_, c)| *c == code)
+            .or_else(|| DOM_ALIASES.iter().find(|(_, c)| *c == code))
+            .map(|(key, _)| *key)
+    }
+
+    /// Convert to a DOM `KeyboardEvent.code`, if there's one for this key
+    /// (see [`Key::from_dom_code()`] for the keys that don't have one).
+    pub fn to_dom_code(self) -> Option<&'static str> {
+        DOM.iter().find(|(k, _)| *k == self).map(|(_, code)| *code)
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match NAMES.iter().find(|(key, _)| key == self) {
+            Some((_, name)) => write!(f, "{}", name),
+            None => Debug::fmt(self, f),
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = ParseError;
+
+    /// Parse the name of a key (as displayed), case-insensitive.  The
+    /// character a key types without shift is also accepted (for example `"1"`
+    /// or `"/"`).
+    fn from_str(s: &str) -> std::result::Result<Self, ParseError> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        if let Some((key, _)) =
+            NAMES.iter().find(|(_, name)| name.eq_ignore_ascii_case(s))
+        {
+            return Ok(*key);
+        }
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_graphic() => {
+                Key::from_keysym(c.into())
+            }
+            _ => None,
+        }
+        .ok_or_else(|| ParseError::UnknownKey(s.to_string()))
+    }
+}
+
+/// Name of each key.
+const NAMES: &[(Key, &str)] = &[
+    (Key::Esc, "Esc"),
+    (Key::One, "One"),
+    (Key::Two, "Two"),
+    (Key::Three, "Three"),
+    (Key::Four, "Four"),
+    (Key::Five, "Five"),
+    (Key::Six, "Six"),
+    (Key::Seven, "Seven"),
+    (Key::Eight, "Eight"),
+    (Key::Nine, "Nine"),
+    (Key::Zero, "Zero"),
+    (Key::Minus, "Minus"),
+    (Key::Equal, "Equal"),
+    (Key::Backslash, "Backslash"),
+    (Key::Backtick, "Backtick"),
+    (Key::Tab, "Tab"),
+    (Key::Q, "Q"),
+    (Key::W, "W"),
+    (Key::E, "E"),
+    (Key::R, "R"),
+    (Key::T, "T"),
+    (Key::Y, "Y"),
+    (Key::U, "U"),
+    (Key::I, "I"),
+    (Key::O, "O"),
+    (Key::P, "P"),
+    (Key::BracketOpen, "BracketOpen"),
+    (Key::BracketClose, "BracketClose"),
+    (Key::Delete, "Delete"),
+    (Key::Caps, "Caps"),
+    (Key::A, "A"),
+    (Key::S, "S"),
+    (Key::D, "D"),
+    (Key::F, "F"),
+    (Key::G, "G"),
+    (Key::H, "H"),
+    (Key::J, "J"),
+    (Key::K, "K"),
+    (Key::L, "L"),
+    (Key::Semicolon, "Semicolon"),
+    (Key::Apostrophe, "Apostrophe"),
+    (Key::Enter, "Enter"),
+    (Key::LShift, "LShift"),
+    (Key::Z, "Z"),
+    (Key::X, "X"),
+    (Key::C, "C"),
+    (Key::V, "V"),
+    (Key::B, "B"),
+    (Key::N, "N"),
+    (Key::M, "M"),
+    (Key::Comma, "Comma"),
+    (Key::Period, "Period"),
+    (Key::Slash, "Slash"),
+    (Key::RShift, "RShift"),
+    (Key::Up, "Up"),
+    (Key::LCtrl, "LCtrl"),
+    (Key::LAlt, "LAlt"),
+    (Key::Space, "Space"),
+    (Key::Thumb, "Thumb"),
+    (Key::RAlt, "RAlt"),
+    (Key::RCtrl, "RCtrl"),
+    (Key::Left, "Left"),
+    (Key::Down, "Down"),
+    (Key::Right, "Right"),
+    (Key::Power, "Power"),
+    (Key::F1, "F1"),
+    (Key::F2, "F2"),
+    (Key::F3, "F3"),
+    (Key::F4, "F4"),
+    (Key::F5, "F5"),
+    (Key::F6, "F6"),
+    (Key::F7, "F7"),
+    (Key::F8, "F8"),
+    (Key::F9, "F9"),
+    (Key::F10, "F10"),
+    (Key::F11, "F11"),
+    (Key::F12, "F12"),
+    (Key::Screenshot, "Screenshot"),
+    (Key::Insert, "Insert"),
+    (Key::Next, "Next"),
+    (Key::Microphone, "Microphone"),
+    (Key::Web, "Web"),
+    (Key::Email, "Email"),
+    (Key::Player, "Player"),
+    (Key::Calculator, "Calculator"),
+    (Key::Del, "Del"),
+    (Key::Prev, "Prev"),
+    (Key::Airplane, "Airplane"),
+    (Key::Scroll, "Scroll"),
+    (Key::Display, "Display"),
+    (Key::Han, "Han"),
+    (Key::Hanja, "Hanja"),
+    (Key::Kana, "Kana"),
+    (Key::Convert, "Convert"),
+    (Key::NonConvert, "NonConvert"),
+    (Key::NumEnter, "NumEnter"),
+    (Key::Mute, "Mute"),
+    (Key::Quieter, "Quieter"),
+    (Key::Louder, "Louder"),
+    (Key::Dimmer, "Dimmer"),
+    (Key::Brighter, "Brighter"),
+    (Key::Num, "Num"),
+    (Key::Menu, "Menu"),
+    (Key::Break, "Break"),
+    (Key::Clear, "Clear"),
+    (Key::Pause, "Pause"),
+    (Key::PageUp, "PageUp"),
+    (Key::Play, "Play"),
+    (Key::Stop, "Stop"),
+    (Key::Home, "Home"),
+    (Key::PageDown, "PageDown"),
+    (Key::End, "End"),
+    (Key::NumOne, "NumOne"),
+    (Key::NumTwo, "NumTwo"),
+    (Key::NumThree, "NumThree"),
+    (Key::NumFour, "NumFour"),
+    (Key::NumFive, "NumFive"),
+    (Key::NumSix, "NumSix"),
+    (Key::NumSeven, "NumSeven"),
+    (Key::NumEight, "NumEight"),
+    (Key::NumNine, "NumNine"),
+    (Key::NumZero, "NumZero"),
+    (Key::NumPeriod, "NumPeriod"),
+    (Key::NumSlash, "NumSlash"),
+    (Key::NumStar, "NumStar"),
+    (Key::NumMinus, "NumMinus"),
+    (Key::NumPlus, "NumPlus"),
+    (Key::NumEqual, "NumEqual"),
+    (Key::NumComma, "NumComma"),
+];
+
+/// Evdev keycode for each key (keys without one are missing).
+const EVDEV: &[(Key, u16)] = &[
+    (Key::Esc, 1),
+    (Key::One, 2),
+    (Key::Two, 3),
+    (Key::Three, 4),
+    (Key::Four, 5),
+    (Key::Five, 6),
+    (Key::Six, 7),
+    (Key::Seven, 8),
+    (Key::Eight, 9),
+    (Key::Nine, 10),
+    (Key::Zero, 11),
+    (Key::Minus, 12),
+    (Key::Equal, 13),
+    (Key::Delete, 14),
+    (Key::Tab, 15),
+    (Key::Q, 16),
+    (Key::W, 17),
+    (Key::E, 18),
+    (Key::R, 19),
+    (Key::T, 20),
+    (Key::Y, 21),
+    (Key::U, 22),
+    (Key::I, 23),
+    (Key::O, 24),
+    (Key::P, 25),
+    (Key::BracketOpen, 26),
+    (Key::BracketClose, 27),
+    (Key::Enter, 28),
+    (Key::LCtrl, 29),
+    (Key::A, 30),
+    (Key::S, 31),
+    (Key::D, 32),
+    (Key::F, 33),
+    (Key::G, 34),
+    (Key::H, 35),
+    (Key::J, 36),
+    (Key::K, 37),
+    (Key::L, 38),
+    (Key::Semicolon, 39),
+    (Key::Apostrophe, 40),
+    (Key::Backtick, 41),
+    (Key::LShift, 42),
+    (Key::Backslash, 43),
+    (Key::Z, 44),
+    (Key::X, 45),
+    (Key::C, 46),
+    (Key::V, 47),
+    (Key::B, 48),
+    (Key::N, 49),
+    (Key::M, 50),
+    (Key::Comma, 51),
+    (Key::Period, 52),
+    (Key::Slash, 53),
+    (Key::RShift, 54),
+    (Key::NumStar, 55),
+    (Key::LAlt, 56),
+    (Key::Space, 57),
+    (Key::Caps, 58),
+    (Key::F1, 59),
+    (Key::F2, 60),
+    (Key::F3, 61),
+    (Key::F4, 62),
+    (Key::F5, 63),
+    (Key::F6, 64),
+    (Key::F7, 65),
+    (Key::F8, 66),
+    (Key::F9, 67),
+    (Key::F10, 68),
+    (Key::Num, 69),
+    (Key::Scroll, 70),
+    (Key::NumSeven, 71),
+    (Key::NumEight, 72),
+    (Key::NumNine, 73),
+    (Key::NumMinus, 74),
+    (Key::NumFour, 75),
+    (Key::NumFive, 76),
+    (Key::NumSix, 77),
+    (Key::NumPlus, 78),
+    (Key::NumOne, 79),
+    (Key::NumTwo, 80),
+    (Key::NumThree, 81),
+    (Key::NumZero, 82),
+    (Key::NumPeriod, 83),
+    (Key::F11, 87),
+    (Key::F12, 88),
+    (Key::Convert, 92),
+    (Key::Kana, 93),
+    (Key::NonConvert, 94),
+    (Key::NumEnter, 96),
+    (Key::RCtrl, 97),
+    (Key::NumSlash, 98),
+    (Key::Screenshot, 99),
+    (Key::RAlt, 100),
+    (Key::Home, 102),
+    (Key::Up, 103),
+    (Key::PageUp, 104),
+    (Key::Left, 105),
+    (Key::Right, 106),
+    (Key::End, 107),
+    (Key::Down, 108),
+    (Key::PageDown, 109),
+    (Key::Insert, 110),
+    (Key::Del, 111),
+    (Key::Mute, 113),
+    (Key::Quieter, 114),
+    (Key::Louder, 115),
+    (Key::Power, 116),
+    (Key::NumEqual, 117),
+    (Key::Pause, 119),
+    (Key::NumComma, 121),
+    (Key::Han, 122),
+    (Key::Hanja, 123),
+    (Key::Menu, 127),
+    (Key::Calculator, 140),
+    (Key::Web, 150),
+    (Key::Email, 155),
+    (Key::Next, 163),
+    (Key::Play, 164),
+    (Key::Prev, 165),
+    (Key::Stop, 166),
+    (Key::Dimmer, 224),
+    (Key::Brighter, 225),
+    (Key::Player, 226),
+    (Key::Display, 227),
+    (Key::Airplane, 247),
+    (Key::Microphone, 248),
+    (Key::Clear, 0x163),
+    (Key::Break, 0x19B),
+];
+
+/// Other evdev keycodes that convert to a key, but not back.
+const EVDEV_ALIASES: &[(Key, u16)] = &[
+    (Key::Backslash, 86),   // KEY_102ND
+    (Key::Menu, 139),       // KEY_MENU
+    (Key::Play, 207),       // KEY_PLAY
+    (Key::Screenshot, 210), // KEY_PRINT
+];
+
+/// USB HID usage page and ID for each key (keys without one are missing).
+const HID: &[(Key, (u16, u16))] = &[
+    (Key::A, (0x07, 0x04)),
+    (Key::B, (0x07, 0x05)),
+    (Key::C, (0x07, 0x06)),
+    (Key::D, (0x07, 0x07)),
+    (Key::E, (0x07, 0x08)),
+    (Key::F, (0x07, 0x09)),
+    (Key::G, (0x07, 0x0A)),
+    (Key::H, (0x07, 0x0B)),
+    (Key::I, (0x07, 0x0C)),
+    (Key::J, (0x07, 0x0D)),
+    (Key::K, (0x07, 0x0E)),
+    (Key::L, (0x07, 0x0F)),
+    (Key::M, (0x07, 0x10)),
+    (Key::N, (0x07, 0x11)),
+    (Key::O, (0x07, 0x12)),
+    (Key::P, (0x07, 0x13)),
+    (Key::Q, (0x07, 0x14)),
+    (Key::R, (0x07, 0x15)),
+    (Key::S, (0x07, 0x16)),
+    (Key::T, (0x07, 0x17)),
+    (Key::U, (0x07, 0x18)),
+    (Key::V, (0x07, 0x19)),
+    (Key::W, (0x07, 0x1A)),
+    (Key::X, (0x07, 0x1B)),
+    (Key::Y, (0x07, 0x1C)),
+    (Key::Z, (0x07, 0x1D)),
+    (Key::One, (0x07, 0x1E)),
+    (Key::Two, (0x07, 0x1F)),
+    (Key::Three, (0x07, 0x20)),
+    (Key::Four, (0x07, 0x21)),
+    (Key::Five, (0x07, 0x22)),
+    (Key::Six, (0x07, 0x23)),
+    (Key::Seven, (0x07, 0x24)),
+    (Key::Eight, (0x07, 0x25)),
+    (Key::Nine, (0x07, 0x26)),
+    (Key::Zero, (0x07, 0x27)),
+    (Key::Enter, (0x07, 0x28)),
+    (Key::Esc, (0x07, 0x29)),
+    (Key::Delete, (0x07, 0x2A)),
+    (Key::Tab, (0x07, 0x2B)),
+    (Key::Space, (0x07, 0x2C)),
+    (Key::Minus, (0x07, 0x2D)),
+    (Key::Equal, (0x07, 0x2E)),
+    (Key::BracketOpen, (0x07, 0x2F)),
+    (Key::BracketClose, (0x07, 0x30)),
+    (Key::Backslash, (0x07, 0x31)),
+    (Key::Semicolon, (0x07, 0x33)),
+    (Key::Apostrophe, (0x07, 0x34)),
+    (Key::Backtick, (0x07, 0x35)),
+    (Key::Comma, (0x07, 0x36)),
+    (Key::Period, (0x07, 0x37)),
+    (Key::Slash, (0x07, 0x38)),
+    (Key::Caps, (0x07, 0x39)),
+    (Key::F1, (0x07, 0x3A)),
+    (Key::F2, (0x07, 0x3B)),
+    (Key::F3, (0x07, 0x3C)),
+    (Key::F4, (0x07, 0x3D)),
+    (Key::F5, (0x07, 0x3E)),
+    (Key::F6, (0x07, 0x3F)),
+    (Key::F7, (0x07, 0x40)),
+    (Key::F8, (0x07, 0x41)),
+    (Key::F9, (0x07, 0x42)),
+    (Key::F10, (0x07, 0x43)),
+    (Key::F11, (0x07, 0x44)),
+    (Key::F12, (0x07, 0x45)),
+    (Key::Screenshot, (0x07, 0x46)),
+    (Key::Scroll, (0x07, 0x47)),
+    (Key::Pause, (0x07, 0x48)),
+    (Key::Insert, (0x07, 0x49)),
+    (Key::Home, (0x07, 0x4A)),
+    (Key::PageUp, (0x07, 0x4B)),
+    (Key::Del, (0x07, 0x4C)),
+    (Key::End, (0x07, 0x4D)),
+    (Key::PageDown, (0x07, 0x4E)),
+    (Key::Right, (0x07, 0x4F)),
+    (Key::Left, (0x07, 0x50)),
+    (Key::Down, (0x07, 0x51)),
+    (Key::Up, (0x07, 0x52)),
+    (Key::Num, (0x07, 0x53)),
+    (Key::NumSlash, (0x07, 0x54)),
+    (Key::NumStar, (0x07, 0x55)),
+    (Key::NumMinus, (0x07, 0x56)),
+    (Key::NumPlus, (0x07, 0x57)),
+    (Key::NumEnter, (0x07, 0x58)),
+    (Key::NumOne, (0x07, 0x59)),
+    (Key::NumTwo, (0x07, 0x5A)),
+    (Key::NumThree, (0x07, 0x5B)),
+    (Key::NumFour, (0x07, 0x5C)),
+    (Key::NumFive, (0x07, 0x5D)),
+    (Key::NumSix, (0x07, 0x5E)),
+    (Key::NumSeven, (0x07, 0x5F)),
+    (Key::NumEight, (0x07, 0x60)),
+    (Key::NumNine, (0x07, 0x61)),
+    (Key::NumZero, (0x07, 0x62)),
+    (Key::NumPeriod, (0x07, 0x63)),
+    (Key::Menu, (0x07, 0x65)),
+    (Key::Power, (0x07, 0x66)),
+    (Key::NumEqual, (0x07, 0x67)),
+    (Key::NumComma, (0x07, 0x85)),
+    (Key::Kana, (0x07, 0x88)),
+    (Key::Convert, (0x07, 0x8A)),
+    (Key::NonConvert, (0x07, 0x8B)),
+    (Key::Han, (0x07, 0x90)),
+    (Key::Hanja, (0x07, 0x91)),
+    (Key::Clear, (0x07, 0x9C)),
+    (Key::LCtrl, (0x07, 0xE0)),
+    (Key::LShift, (0x07, 0xE1)),
+    (Key::LAlt, (0x07, 0xE2)),
+    (Key::RCtrl, (0x07, 0xE4)),
+    (Key::RShift, (0x07, 0xE5)),
+    (Key::RAlt, (0x07, 0xE6)),
+    (Key::Brighter, (0x0C, 0x6F)),
+    (Key::Dimmer, (0x0C, 0x70)),
+    (Key::Next, (0x0C, 0xB5)),
+    (Key::Prev, (0x0C, 0xB6)),
+    (Key::Stop, (0x0C, 0xB7)),
+    (Key::Play, (0x0C, 0xCD)),
+    (Key::Mute, (0x0C, 0xE2)),
+    (Key::Louder, (0x0C, 0xE9)),
+    (Key::Quieter, (0x0C, 0xEA)),
+    (Key::Player, (0x0C, 0x183)),
+    (Key::Email, (0x0C, 0x18A)),
+    (Key::Calculator, (0x0C, 0x192)),
+    (Key::Web, (0x0C, 0x196)),
+    (Key::Microphone, (0x01, 0xA9)),
+    (Key::Display, (0x01, 0xB5)),
+    (Key::Airplane, (0x01, 0xC6)),
+];
+
+/// Other USB HID usages that convert to a key, but not back.
+const HID_ALIASES: &[(Key, (u16, u16))] = &[
+    (Key::Backslash, (0x07, 0x32)), // Non-US # and ~
+    (Key::Backslash, (0x07, 0x64)), // Non-US \ and |
+    (Key::Mute, (0x07, 0x7F)),      // Keyboard Mute
+    (Key::Louder, (0x07, 0x80)),    // Keyboard Volume Up
+    (Key::Quieter, (0x07, 0x81)),   // Keyboard Volume Down
+    (Key::Power, (0x01, 0x81)),     // System Power Down
+    (Key::Power, (0x0C, 0x30)),     // Consumer Power
+    (Key::Play, (0x0C, 0xB0)),      // Play
+];
+
+/// X11 keysym for each key (keys without one are missing).
+const KEYSYM: &[(Key, u32)] = &[
+    (Key::Space, 0x0020),
+    (Key::Apostrophe, 0x0027),
+    (Key::Comma, 0x002C),
+    (Key::Minus, 0x002D),
+    (Key::Period, 0x002E),
+    (Key::Slash, 0x002F),
+    (Key::Zero, 0x0030),
+    (Key::One, 0x0031),
+    (Key::Two, 0x0032),
+    (Key::Three, 0x0033),
+    (Key::Four, 0x0034),
+    (Key::Five, 0x0035),
+    (Key::Six, 0x0036),
+    (Key::Seven, 0x0037),
+    (Key::Eight, 0x0038),
+    (Key::Nine, 0x0039),
+    (Key::Semicolon, 0x003B),
+    (Key::Equal, 0x003D),
+    (Key::BracketOpen, 0x005B),
+    (Key::Backslash, 0x005C),
+    (Key::BracketClose, 0x005D),
+    (Key::Backtick, 0x0060),
+    (Key::A, 0x0061),
+    (Key::B, 0x0062),
+    (Key::C, 0x0063),
+    (Key::D, 0x0064),
+    (Key::E, 0x0065),
+    (Key::F, 0x0066),
+    (Key::G, 0x0067),
+    (Key::H, 0x0068),
+    (Key::I, 0x0069),
+    (Key::J, 0x006A),
+    (Key::K, 0x006B),
+    (Key::L, 0x006C),
+    (Key::M, 0x006D),
+    (Key::N, 0x006E),
+    (Key::O, 0x006F),
+    (Key::P, 0x0070),
+    (Key::Q, 0x0071),
+    (Key::R, 0x0072),
+    (Key::S, 0x0073),
+    (Key::T, 0x0074),
+    (Key::U, 0x0075),
+    (Key::V, 0x0076),
+    (Key::W, 0x0077),
+    (Key::X, 0x0078),
+    (Key::Y, 0x0079),
+    (Key::Z, 0x007A),
+    (Key::Thumb, 0xFE03),      // ISO_Level3_Shift
+    (Key::Delete, 0xFF08),     // BackSpace
+    (Key::Tab, 0xFF09),        // Tab
+    (Key::Clear, 0xFF0B),      // Clear
+    (Key::Enter, 0xFF0D),      // Return
+    (Key::Pause, 0xFF13),      // Pause
+    (Key::Scroll, 0xFF14),     // Scroll_Lock
+    (Key::Esc, 0xFF1B),        // Escape
+    (Key::NonConvert, 0xFF22), // Muhenkan
+    (Key::Convert, 0xFF23),    // Henkan
+    (Key::Kana, 0xFF27),       // Hiragana_Katakana
+    (Key::Han, 0xFF31),        // Hangul
+    (Key::Hanja, 0xFF34),      // Hangul_Hanja
+    (Key::Home, 0xFF50),       // Home
+    (Key::Left, 0xFF51),       // Left
+    (Key::Up, 0xFF52),         // Up
+    (Key::Right, 0xFF53),      // Right
+    (Key::Down, 0xFF54),       // Down
+    (Key::PageUp, 0xFF55),     // Prior
+    (Key::PageDown, 0xFF56),   // Next
+    (Key::End, 0xFF57),        // End
+    (Key::Screenshot, 0xFF61), // Print
+    (Key::Insert, 0xFF63),     // Insert
+    (Key::Menu, 0xFF67),       // Menu
+    (Key::Break, 0xFF6B),      // Break
+    (Key::Num, 0xFF7F),        // Num_Lock
+    (Key::NumEnter, 0xFF8D),   // KP_Enter
+    (Key::NumStar, 0xFFAA),    // KP_Multiply
+    (Key::NumPlus, 0xFFAB),    // KP_Add
+    (Key::NumComma, 0xFFAC),   // KP_Separator
+    (Key::NumMinus, 0xFFAD),   // KP_Subtract
+    (Key::NumPeriod, 0xFFAE),  // KP_Decimal
+    (Key::NumSlash, 0xFFAF),   // KP_Divide
+    (Key::NumZero, 0xFFB0),    // KP_0
+    (Key::NumOne, 0xFFB1),     // KP_1
+    (Key::NumTwo, 0xFFB2),     // KP_2
+    (Key::NumThree, 0xFFB3),   // KP_3
+    (Key::NumFour, 0xFFB4),    // KP_4
+    (Key::NumFive, 0xFFB5),    // KP_5
+    (Key::NumSix, 0xFFB6),     // KP_6
+    (Key::NumSeven, 0xFFB7),   // KP_7
+    (Key::NumEight, 0xFFB8),   // KP_8
+    (Key::NumNine, 0xFFB9),    // KP_9
+    (Key::NumEqual, 0xFFBD),   // KP_Equal
+    (Key::F1, 0xFFBE),
+    (Key::F2, 0xFFBF),
+    (Key::F3, 0xFFC0),
+    (Key::F4, 0xFFC1),
+    (Key::F5, 0xFFC2),
+    (Key::F6, 0xFFC3),
+    (Key::F7, 0xFFC4),
+    (Key::F8, 0xFFC5),
+    (Key::F9, 0xFFC6),
+    (Key::F10, 0xFFC7),
+    (Key::F11, 0xFFC8),
+    (Key::F12, 0xFFC9),
+    (Key::LShift, 0xFFE1),          // Shift_L
+    (Key::RShift, 0xFFE2),          // Shift_R
+    (Key::LCtrl, 0xFFE3),           // Control_L
+    (Key::RCtrl, 0xFFE4),           // Control_R
+    (Key::Caps, 0xFFE5),            // Caps_Lock
+    (Key::LAlt, 0xFFE9),            // Alt_L
+    (Key::RAlt, 0xFFEA),            // Alt_R
+    (Key::Del, 0xFFFF),             // Delete
+    (Key::Brighter, 0x1008_FF02),   // XF86MonBrightnessUp
+    (Key::Dimmer, 0x1008_FF03),     // XF86MonBrightnessDown
+    (Key::Quieter, 0x1008_FF11),    // XF86AudioLowerVolume
+    (Key::Mute, 0x1008_FF12),       // XF86AudioMute
+    (Key::Louder, 0x1008_FF13),     // XF86AudioRaiseVolume
+    (Key::Play, 0x1008_FF14),       // XF86AudioPlay
+    (Key::Stop, 0x1008_FF15),       // XF86AudioStop
+    (Key::Prev, 0x1008_FF16),       // XF86AudioPrev
+    (Key::Next, 0x1008_FF17),       // XF86AudioNext
+    (Key::Email, 0x1008_FF19),      // XF86Mail
+    (Key::Calculator, 0x1008_FF1D), // XF86Calculator
+    (Key::Power, 0x1008_FF2A),      // XF86PowerOff
+    (Key::Web, 0x1008_FF2E),        // XF86WWW
+    (Key::Player, 0x1008_FF32),     // XF86AudioMedia
+    (Key::Display, 0x1008_FF59),    // XF86Display
+    (Key::Microphone, 0x1008_FFB2), // XF86AudioMicMute
+    (Key::Airplane, 0x1008_FFB5),   // XF86RFKill
+];
+
+/// Other X11 keysyms that convert to a key, but not back.
+const KEYSYM_ALIASES: &[(Key, u32)] = &[
+    (Key::A, 0x0041),
+    (Key::B, 0x0042),
+    (Key::C, 0x0043),
+    (Key::D, 0x0044),
+    (Key::E, 0x0045),
+    (Key::F, 0x0046),
+    (Key::G, 0x0047),
+    (Key::H, 0x0048),
+    (Key::I, 0x0049),
+    (Key::J, 0x004A),
+    (Key::K, 0x004B),
+    (Key::L, 0x004C),
+    (Key::M, 0x004D),
+    (Key::N, 0x004E),
+    (Key::O, 0x004F),
+    (Key::P, 0x0050),
+    (Key::Q, 0x0051),
+    (Key::R, 0x0052),
+    (Key::S, 0x0053),
+    (Key::T, 0x0054),
+    (Key::U, 0x0055),
+    (Key::V, 0x0056),
+    (Key::W, 0x0057),
+    (Key::X, 0x0058),
+    (Key::Y, 0x0059),
+    (Key::Z, 0x005A),
+    (Key::Tab, 0xFE20),        // ISO_Left_Tab
+    (Key::Screenshot, 0xFF15), // Sys_Req
+    (Key::Thumb, 0xFF7E),      // Mode_switch
+    (Key::NumSeven, 0xFF95),   // KP_Home
+    (Key::NumFour, 0xFF96),    // KP_Left
+    (Key::NumEight, 0xFF97),   // KP_Up
+    (Key::NumSix, 0xFF98),     // KP_Right
+    (Key::NumTwo, 0xFF99),     // KP_Down
+    (Key::NumNine, 0xFF9A),    // KP_Prior
+    (Key::NumThree, 0xFF9B),   // KP_Next
+    (Key::NumOne, 0xFF9C),     // KP_End
+    (Key::NumFive, 0xFF9D),    // KP_Begin
+    (Key::NumZero, 0xFF9E),    // KP_Insert
+    (Key::NumPeriod, 0xFF9F),  // KP_Delete
+    (Key::Play, 0x1008_FF31),  // XF86AudioPause
+];
+
+/// Windows virtual-key code for each key (keys without one are missing).
+const VK: &[(Key, u16)] = &[
+    (Key::Break, 0x03),      // VK_CANCEL
+    (Key::Delete, 0x08),     // VK_BACK
+    (Key::Tab, 0x09),        // VK_TAB
+    (Key::Clear, 0x0C),      // VK_CLEAR
+    (Key::Enter, 0x0D),      // VK_RETURN
+    (Key::Pause, 0x13),      // VK_PAUSE
+    (Key::Caps, 0x14),       // VK_CAPITAL
+    (Key::Han, 0x15),        // VK_HANGUL
+    (Key::Hanja, 0x19),      // VK_HANJA
+    (Key::Esc, 0x1B),        // VK_ESCAPE
+    (Key::Convert, 0x1C),    // VK_CONVERT
+    (Key::NonConvert, 0x1D), // VK_NONCONVERT
+    (Key::Space, 0x20),      // VK_SPACE
+    (Key::PageUp, 0x21),     // VK_PRIOR
+    (Key::PageDown, 0x22),   // VK_NEXT
+    (Key::End, 0x23),        // VK_END
+    (Key::Home, 0x24),       // VK_HOME
+    (Key::Left, 0x25),       // VK_LEFT
+    (Key::Up, 0x26),         // VK_UP
+    (Key::Right, 0x27),      // VK_RIGHT
+    (Key::Down, 0x28),       // VK_DOWN
+    (Key::Screenshot, 0x2C), // VK_SNAPSHOT
+    (Key::Insert, 0x2D),     // VK_INSERT
+    (Key::Del, 0x2E),        // VK_DELETE
+    (Key::Zero, 0x30),
+    (Key::One, 0x31),
+    (Key::Two, 0x32),
+    (Key::Three, 0x33),
+    (Key::Four, 0x34),
+    (Key::Five, 0x35),
+    (Key::Six, 0x36),
+    (Key::Seven, 0x37),
+    (Key::Eight, 0x38),
+    (Key::Nine, 0x39),
+    (Key::A, 0x41),
+    (Key::B, 0x42),
+    (Key::C, 0x43),
+    (Key::D, 0x44),
+    (Key::E, 0x45),
+    (Key::F, 0x46),
+    (Key::G, 0x47),
+    (Key::H, 0x48),
+    (Key::I, 0x49),
+    (Key::J, 0x4A),
+    (Key::K, 0x4B),
+    (Key::L, 0x4C),
+    (Key::M, 0x4D),
+    (Key::N, 0x4E),
+    (Key::O, 0x4F),
+    (Key::P, 0x50),
+    (Key::Q, 0x51),
+    (Key::R, 0x52),
+    (Key::S, 0x53),
+    (Key::T, 0x54),
+    (Key::U, 0x55),
+    (Key::V, 0x56),
+    (Key::W, 0x57),
+    (Key::X, 0x58),
+    (Key::Y, 0x59),
+    (Key::Z, 0x5A),
+    (Key::Menu, 0x5D),      // VK_APPS
+    (Key::NumZero, 0x60),   // VK_NUMPAD0
+    (Key::NumOne, 0x61),    // VK_NUMPAD1
+    (Key::NumTwo, 0x62),    // VK_NUMPAD2
+    (Key::NumThree, 0x63),  // VK_NUMPAD3
+    (Key::NumFour, 0x64),   // VK_NUMPAD4
+    (Key::NumFive, 0x65),   // VK_NUMPAD5
+    (Key::NumSix, 0x66),    // VK_NUMPAD6
+    (Key::NumSeven, 0x67),  // VK_NUMPAD7
+    (Key::NumEight, 0x68),  // VK_NUMPAD8
+    (Key::NumNine, 0x69),   // VK_NUMPAD9
+    (Key::NumStar, 0x6A),   // VK_MULTIPLY
+    (Key::NumPlus, 0x6B),   // VK_ADD
+    (Key::NumComma, 0x6C),  // VK_SEPARATOR
+    (Key::NumMinus, 0x6D),  // VK_SUBTRACT
+    (Key::NumPeriod, 0x6E), // VK_DECIMAL
+    (Key::NumSlash, 0x6F),  // VK_DIVIDE
+    (Key::F1, 0x70),
+    (Key::F2, 0x71),
+    (Key::F3, 0x72),
+    (Key::F4, 0x73),
+    (Key::F5, 0x74),
+    (Key::F6, 0x75),
+    (Key::F7, 0x76),
+    (Key::F8, 0x77),
+    (Key::F9, 0x78),
+    (Key::F10, 0x79),
+    (Key::F11, 0x7A),
+    (Key::F12, 0x7B),
+    (Key::Num, 0x90),          // VK_NUMLOCK
+    (Key::Scroll, 0x91),       // VK_SCROLL
+    (Key::NumEqual, 0x92),     // VK_OEM_NEC_EQUAL
+    (Key::LShift, 0xA0),       // VK_LSHIFT
+    (Key::RShift, 0xA1),       // VK_RSHIFT
+    (Key::LCtrl, 0xA2),        // VK_LCONTROL
+    (Key::RCtrl, 0xA3),        // VK_RCONTROL
+    (Key::LAlt, 0xA4),         // VK_LMENU
+    (Key::RAlt, 0xA5),         // VK_RMENU
+    (Key::Web, 0xAC),          // VK_BROWSER_HOME
+    (Key::Mute, 0xAD),         // VK_VOLUME_MUTE
+    (Key::Quieter, 0xAE),      // VK_VOLUME_DOWN
+    (Key::Louder, 0xAF),       // VK_VOLUME_UP
+    (Key::Next, 0xB0),         // VK_MEDIA_NEXT_TRACK
+    (Key::Prev, 0xB1),         // VK_MEDIA_PREV_TRACK
+    (Key::Stop, 0xB2),         // VK_MEDIA_STOP
+    (Key::Play, 0xB3),         // VK_MEDIA_PLAY_PAUSE
+    (Key::Email, 0xB4),        // VK_LAUNCH_MAIL
+    (Key::Player, 0xB5),       // VK_LAUNCH_MEDIA_SELECT
+    (Key::Calculator, 0xB7),   // VK_LAUNCH_APP2
+    (Key::Semicolon, 0xBA),    // VK_OEM_1
+    (Key::Equal, 0xBB),        // VK_OEM_PLUS
+    (Key::Comma, 0xBC),        // VK_OEM_COMMA
+    (Key::Minus, 0xBD),        // VK_OEM_MINUS
+    (Key::Period, 0xBE),       // VK_OEM_PERIOD
+    (Key::Slash, 0xBF),        // VK_OEM_2
+    (Key::Backtick, 0xC0),     // VK_OEM_3
+    (Key::BracketOpen, 0xDB),  // VK_OEM_4
+    (Key::Backslash, 0xDC),    // VK_OEM_5
+    (Key::BracketClose, 0xDD), // VK_OEM_6
+    (Key::Apostrophe, 0xDE),   // VK_OEM_7
+    (Key::Kana, 0xF2),         // VK_OEM_COPY (Hiragana)
+];
+
+/// Other Windows virtual-key codes that convert to a key, but not back.
+const VK_ALIASES: &[(Key, u16)] = &[
+    (Key::LShift, 0x10),     // VK_SHIFT
+    (Key::LCtrl, 0x11),      // VK_CONTROL
+    (Key::LAlt, 0x12),       // VK_MENU
+    (Key::Screenshot, 0x2A), // VK_PRINT
+    (Key::Backslash, 0xE2),  // VK_OEM_102
+    (Key::Clear, 0xFE),      // VK_OEM_CLEAR
+];
+
+/// DOM `KeyboardEvent.code` for each key (keys without one are missing).
+const DOM: &[(Key, &str)] = &[
+    (Key::Esc, "Escape"),
+    (Key::One, "Digit1"),
+    (Key::Two, "Digit2"),
+    (Key::Three, "Digit3"),
+    (Key::Four, "Digit4"),
+    (Key::Five, "Digit5"),
+    (Key::Six, "Digit6"),
+    (Key::Seven, "Digit7"),
+    (Key::Eight, "Digit8"),
+    (Key::Nine, "Digit9"),
+    (Key::Zero, "Digit0"),
+    (Key::Minus, "Minus"),
+    (Key::Equal, "Equal"),
+    (Key::Backslash, "Backslash"),
+    (Key::Backtick, "Backquote"),
+    (Key::Tab, "Tab"),
+    (Key::Q, "KeyQ"),
+    (Key::W, "KeyW"),
+    (Key::E, "KeyE"),
+    (Key::R, "KeyR"),
+    (Key::T, "KeyT"),
+    (Key::Y, "KeyY"),
+    (Key::U, "KeyU"),
+    (Key::I, "KeyI"),
+    (Key::O, "KeyO"),
+    (Key::P, "KeyP"),
+    (Key::BracketOpen, "BracketLeft"),
+    (Key::BracketClose, "BracketRight"),
+    (Key::Delete, "Backspace"),
+    (Key::Caps, "CapsLock"),
+    (Key::A, "KeyA"),
+    (Key::S, "KeyS"),
+    (Key::D, "KeyD"),
+    (Key::F, "KeyF"),
+    (Key::G, "KeyG"),
+    (Key::H, "KeyH"),
+    (Key::J, "KeyJ"),
+    (Key::K, "KeyK"),
+    (Key::L, "KeyL"),
+    (Key::Semicolon, "Semicolon"),
+    (Key::Apostrophe, "Quote"),
+    (Key::Enter, "Enter"),
+    (Key::LShift, "ShiftLeft"),
+    (Key::Z, "KeyZ"),
+    (Key::X, "KeyX"),
+    (Key::C, "KeyC"),
+    (Key::V, "KeyV"),
+    (Key::B, "KeyB"),
+    (Key::N, "KeyN"),
+    (Key::M, "KeyM"),
+    (Key::Comma, "Comma"),
+    (Key::Period, "Period"),
+    (Key::Slash, "Slash"),
+    (Key::RShift, "ShiftRight"),
+    (Key::Up, "ArrowUp"),
+    (Key::LCtrl, "ControlLeft"),
+    (Key::LAlt, "AltLeft"),
+    (Key::Space, "Space"),
+    (Key::RAlt, "AltRight"),
+    (Key::RCtrl, "ControlRight"),
+    (Key::Left, "ArrowLeft"),
+    (Key::Down, "ArrowDown"),
+    (Key::Right, "ArrowRight"),
+    (Key::Power, "Power"),
+    (Key::F1, "F1"),
+    (Key::F2, "F2"),
+    (Key::F3, "F3"),
+    (Key::F4, "F4"),
+    (Key::F5, "F5"),
+    (Key::F6, "F6"),
+    (Key::F7, "F7"),
+    (Key::F8, "F8"),
+    (Key::F9, "F9"),
+    (Key::F10, "F10"),
+    (Key::F11, "F11"),
+    (Key::F12, "F12"),
+    (Key::Screenshot, "PrintScreen"),
+    (Key::Insert, "Insert"),
+    (Key::Next, "MediaTrackNext"),
+    (Key::Microphone, "MicrophoneMuteToggle"),
+    (Key::Web, "BrowserHome"),
+    (Key::Email, "LaunchMail"),
+    (Key::Player, "MediaSelect"),
+    (Key::Calculator, "LaunchApp2"),
+    (Key::Del, "Delete"),
+    (Key::Prev, "MediaTrackPrevious"),
+    (Key::Scroll, "ScrollLock"),
+    (Key::Display, "DisplayToggleIntExt"),
+    (Key::Han, "Lang1"),
+    (Key::Hanja, "Lang2"),
+    (Key::Kana, "KanaMode"),
+    (Key::Convert, "Convert"),
+    (Key::NonConvert, "NonConvert"),
+    (Key::NumEnter, "NumpadEnter"),
+    (Key::Mute, "AudioVolumeMute"),
+    (Key::Quieter, "AudioVolumeDown"),
+    (Key::Louder, "AudioVolumeUp"),
+    (Key::Dimmer, "BrightnessDown"),
+    (Key::Brighter, "BrightnessUp"),
+    (Key::Num, "NumLock"),
+    (Key::Menu, "ContextMenu"),
+    (Key::Clear, "NumpadClear"),
+    (Key::Pause, "Pause"),
+    (Key::PageUp, "PageUp"),
+    (Key::Play, "MediaPlayPause"),
+    (Key::Stop, "MediaStop"),
+    (Key::Home, "Home"),
+    (Key::PageDown, "PageDown"),
+    (Key::End, "End"),
+    (Key::NumOne, "Numpad1"),
+    (Key::NumTwo, "Numpad2"),
+    (Key::NumThree, "Numpad3"),
+    (Key::NumFour, "Numpad4"),
+    (Key::NumFive, "Numpad5"),
+    (Key::NumSix, "Numpad6"),
+    (Key::NumSeven, "Numpad7"),
+    (Key::NumEight, "Numpad8"),
+    (Key::NumNine, "Numpad9"),
+    (Key::NumZero, "Numpad0"),
+    (Key::NumPeriod, "NumpadDecimal"),
+    (Key::NumSlash, "NumpadDivide"),
+    (Key::NumStar, "NumpadMultiply"),
+    (Key::NumMinus, "NumpadSubtract"),
+    (Key::NumPlus, "NumpadAdd"),
+    (Key::NumEqual, "NumpadEqual"),
+    (Key::NumComma, "NumpadComma"),
+];
+
+/// Other DOM `KeyboardEvent.code`s that convert to a key, but not back.
+const DOM_ALIASES: &[(Key, &str)] = &[
+    (Key::Delete, "NumpadBackspace"),
+    (Key::Clear, "NumpadClearEntry"),
+    (Key::NumStar, "NumpadStar"),
+    // ISO and JIS keys that share a `Key`
+    (Key::Backslash, "IntlBackslash"),
+    (Key::Backslash, "IntlRo"),
+    (Key::Backslash, "IntlYen"),
+    (Key::Kana, "Lang3"),
+    (Key::Kana, "Lang4"),
+    (Key::Backtick, "Lang5"),
+    // Names used by older browsers
+    (Key::Mute, "VolumeMute"),
+    (Key::Quieter, "VolumeDown"),
+    (Key::Louder, "VolumeUp"),
+    // Play only (without pause)
+    (Key::Play, "MediaPlay"),
+];
@@ -29,14 +29,17 @@
 //!
 //! /// Event handled by the event loop.
 //! enum Event {
-//!     Input(Input),
+//!     Input(human::Event),
 //! }
 //!
 //! impl State {
 //!     /// Event loop.
 //!     fn event(&mut self, event: Event) {
 //!         match event {
-//!             Event::Input(input) => log!(INFO, "Input: {:?}", input),
+//!             Event::Input(event) => {
+//!                 let (time, input) = (event.time(), event.input());
+//!                 log!(INFO, "Input at {:?}: {:?}", time, input)
+//!             }
 //!         }
 //!     }
 //! }
@@ -44,11 +47,11 @@
 //! /// Start the async executor.
 //! fn main() {
 //!     let mut state = State {};
-//!     let mut input = human::Input::listener();
+//!     let mut input = Input::listener();
 //!
 //!     pasts::Executor::default().spawn(Box::pin(async move {
 //!        loop {
-//!            state.event(Event::Input((&mut input).await));
+//!            state.event(Event::Input(input.next().await));
 //!        }
 //!     }));
 //! }
@@ -76,16 +79,45 @@
     variant_size_differences
 )]
 
+mod clock;
+mod device;
+mod gesture;
 mod input;
 mod key;
 mod btn;
+mod queue;
+mod repeat;
+mod shortcut;
+mod state;
+mod term;
+
+pub mod clipboard;
+pub mod ime;
+pub mod pointer;
 
 #[cfg(target_arch = "wasm32")]
 mod web;
+#[cfg(target_os = "linux")]
+mod evdev;
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+mod tty;
 
-pub use input::{Controller, Input};
-pub use key::{Key, Mod};
+pub use device::{Device, DeviceId, DeviceKind};
+pub use gesture::{Direction, Gesture, GestureRecognizer};
+pub use input::{
+    Controller, Event, Input, Pen, PenTool, Scroll, ScrollSource, Touch,
+};
+pub use key::{Key, KeyAction, Mod};
 pub use btn::Btn;
+pub use queue::{Config, Coordinates};
+pub use repeat::KeyRepeat;
+pub use shortcut::{ParseError, Shortcut};
+pub use state::{KeyboardState, PointerState};
+pub use term::TermParser;
+#[cfg(target_os = "linux")]
+pub use evdev::EvdevDecoder;
 /// Input event from a controller.
 ///
 pub use stick::Event as Controls;
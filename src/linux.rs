@@ -0,0 +1,437 @@
+// Human
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Keyboard and mouse input on Linux, read directly from evdev devices.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{ErrorKind, Read},
+    os::{
+        raw::{c_char, c_int, c_long, c_ulong},
+        unix::{
+            fs::OpenOptionsExt,
+            io::{AsRawFd, FromRawFd, RawFd},
+        },
+    },
+    path::{Path, PathBuf},
+    task::{Context, Poll},
+};
+
+use smelling_salts::{Device, Watcher};
+
+use crate::{
+    clock::{self, CLOCK_MONOTONIC},
+    evdev::{VIRTUAL_HEIGHT, VIRTUAL_WIDTH},
+    pointer,
+    queue::Queue,
+    Config, Device as Info, DeviceId, DeviceKind, EvdevDecoder, Event, Input,
+};
+
+const O_NONBLOCK: c_int = 0o0004000;
+const O_CLOEXEC: c_int = 0o2000000;
+
+const IN_ATTRIB: u32 = 0x0000_0004;
+const IN_CREATE: u32 = 0x0000_0100;
+const IN_DELETE: u32 = 0x0000_0200;
+
+// Event types
+const EV_KEY: usize = 0x01;
+const EV_REL: usize = 0x02;
+const EV_ABS: usize = 0x03;
+const EV_LED: usize = 0x11;
+
+/// `EVIOCGLED(2)`: get the state of a device's LEDs.
+const EVIOCGLED: c_ulong = 0x8002_4519;
+/// `EVIOCSCLOCKID`: set the clock used for event timestamps.
+const EVIOCSCLOCKID: c_ulong = 0x4004_45A0;
+/// `EVIOCGABS(0)`: get the range of an absolute axis (add the axis code).
+const EVIOCGABS: c_ulong = 0x8018_4540;
+/// `EVIOCGRAB`: take (or release) exclusive access to a device's events.
+const EVIOCGRAB: c_ulong = 0x4004_4590;
+
+// Codes used to recognize keyboards, mice and gamepads.
+const KEY_A: usize = 30;
+const KEY_SPACE: usize = 57;
+const BTN_LEFT: usize = 0x110;
+const BTN_JOYSTICK: usize = 0x120;
+const BTN_THUMBR: usize = 0x13E;
+const BTN_TOOL_PEN: usize = 0x140;
+const REL_X: usize = 0x00;
+const REL_Y: usize = 0x01;
+const ABS_X: usize = 0x00;
+const ABS_Y: usize = 0x01;
+const ABS_PRESSURE: usize = 0x18;
+const ABS_TILT_X: usize = 0x1A;
+const ABS_TILT_Y: usize = 0x1B;
+const ABS_MT_SLOT: usize = 0x2F;
+const ABS_MT_POSITION_X: usize = 0x35;
+const ABS_MT_POSITION_Y: usize = 0x36;
+const ABS_MT_PRESSURE: usize = 0x3A;
+const INPUT_PROP_DIRECT: usize = 0x01;
+
+/// `struct input_absinfo`
+#[repr(C)]
+#[derive(Default)]
+struct AbsInfo {
+    value: i32,
+    minimum: i32,
+    maximum: i32,
+    fuzz: i32,
+    flat: i32,
+    resolution: i32,
+}
+
+extern "C" {
+    fn inotify_init1(flags: c_int) -> c_int;
+    fn inotify_add_watch(fd: RawFd, path: *const c_char, mask: u32) -> c_int;
+    fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+}
+
+/// An opened keyboard, mouse or touchscreen.
+struct Kbm {
+    // Async device handle (must be dropped before `file`).
+    device: Device,
+    // The open evdev file.
+    file: File,
+    // Path in `/dev/input/`.
+    path: PathBuf,
+    // ID used for the decoder.
+    id: DeviceId,
+    // Name and kind, for announcing the device.
+    info: Info,
+}
+
+/// Watches `/dev/input/` for keyboards, mice and touchscreens, and decodes
+/// their events.
+pub(crate) struct Hub {
+    // Inotify on `/dev/input/` (device must be dropped before file).
+    watch: Option<(Device, File)>,
+    // Currently connected keyboards, mice and touchscreens.
+    devices: Vec<Kbm>,
+    // Shared decoder for all devices.
+    decoder: EvdevDecoder,
+    // Decoded events waiting to be returned.
+    queue: Queue,
+    // Whether the pointer is locked (mice are grabbed).
+    pointer_lock: bool,
+    // Next ID for a newly connected device.
+    next: usize,
+}
+
+impl Hub {
+    pub(crate) fn new(config: Config) -> Self {
+        let mut queue = Queue::new();
+        queue.configure(config);
+        queue.set_surface(VIRTUAL_WIDTH, VIRTUAL_HEIGHT, 1.0);
+        let mut hub = Hub {
+            watch: inotify(),
+            devices: Vec::new(),
+            decoder: EvdevDecoder::new(),
+            queue,
+            pointer_lock: false,
+            next: 0,
+        };
+        hub.scan();
+        hub
+    }
+
+    pub(crate) fn poll(&mut self, cx: &mut Context<'_>) -> Poll<Event> {
+        let pointer_lock = pointer::requested(cx.waker());
+        if pointer_lock != self.pointer_lock {
+            self.set_pointer_lock(pointer_lock);
+        }
+        if let Some(event) = self.queue.pop() {
+            return Poll::Ready(event);
+        }
+
+        // Check for hotplugged devices.
+        if let Some((_, ref mut file)) = self.watch {
+            let mut buf = [0; 4096];
+            let mut changed = false;
+            while let Ok(len) = file.read(&mut buf) {
+                if len == 0 {
+                    break;
+                }
+                changed = true;
+            }
+            if changed {
+                self.scan();
+            }
+        }
+
+        // Read everything available from each device, removing unplugged ones.
+        let mut buf = [0; 4096];
+        let decoder = &mut self.decoder;
+        let queue = &mut self.queue;
+        self.devices.retain(|kbm| {
+            let mut kbm_file = &kbm.file;
+            loop {
+                match kbm_file.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(len) => decoder.push(kbm.id, &buf[..len]),
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => return true,
+                    Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                    // `ENODEV` once unplugged; any other error (like `EIO`)
+                    // also means the device can't be read anymore.
+                    Err(_) => break,
+                }
+            }
+            decoder.remove(kbm.id);
+            let input = Input::DeviceRemoved(kbm.info.clone());
+            queue.push(
+                Event::new(clock::now(), input).with_device(kbm.info.id()),
+            );
+            false
+        });
+
+        while let Some(event) = self.decoder.pop() {
+            self.queue.push(event);
+        }
+        if let Some(event) = self.queue.pop() {
+            return Poll::Ready(event);
+        }
+
+        // Nothing to do, wait for more events.
+        if let Some((ref device, _)) = self.watch {
+            device.register_waker(cx.waker());
+        }
+        for kbm in self.devices.iter() {
+            kbm.device.register_waker(cx.waker());
+        }
+        Poll::Pending
+    }
+
+    /// Grab or release every mouse, and tell the decoder.
+    fn set_pointer_lock(&mut self, locked: bool) {
+        self.pointer_lock = locked;
+        self.decoder.set_pointer_lock(locked);
+        for kbm in self.devices.iter() {
+            if kbm.info.kind() == DeviceKind::Mouse {
+                grab(&kbm.file, locked);
+            }
+        }
+        let input = Input::PointerLock(locked);
+        self.queue.push(Event::new(clock::now(), input));
+    }
+
+    /// Search `/dev/input/` for keyboards, mice and touchscreens that aren't
+    /// open yet.
+    fn scan(&mut self) {
+        let dir = match fs::read_dir("/dev/input/") {
+            Ok(dir) => dir,
+            Err(_) => return,
+        };
+        for entry in dir.flatten() {
+            let path = entry.path();
+            let is_event = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("event"));
+            if !is_event || self.devices.iter().any(|kbm| kbm.path == path) {
+                continue;
+            }
+            let kind = match kind(&path) {
+                Some(kind) => kind,
+                None => continue,
+            };
+            // Permissions may not be set yet, in which case wait for
+            // `IN_ATTRIB`.
+            let file = match OpenOptions::new()
+                .read(true)
+                .custom_flags(O_NONBLOCK)
+                .open(&path)
+            {
+                Ok(file) => file,
+                Err(_) => continue,
+            };
+            set_clock(&file);
+            if self.pointer_lock && kind == DeviceKind::Mouse {
+                grab(&file, true);
+            }
+            if has_leds(&path) {
+                if let Some(leds) = leds(&file) {
+                    self.decoder.set_leds(leds);
+                }
+            }
+            let device = Device::new(file.as_raw_fd(), Watcher::new().input());
+            let id = DeviceId::new(self.next);
+            self.next += 1;
+            let axes: &[usize] = match kind {
+                DeviceKind::Touchscreen => {
+                    &[ABS_MT_POSITION_X, ABS_MT_POSITION_Y, ABS_MT_PRESSURE]
+                }
+                DeviceKind::Pen => {
+                    &[ABS_X, ABS_Y, ABS_PRESSURE, ABS_TILT_X, ABS_TILT_Y]
+                }
+                _ => &[],
+            };
+            for &code in axes {
+                if let Some((min, max)) = abs_range(&file, code) {
+                    self.decoder.set_abs_range(id, code as u16, min, max);
+                }
+            }
+            let info = Info::new(id, name(&path), kind);
+            let input = Input::DeviceAdded(info.clone());
+            self.queue
+                .push(Event::new(clock::now(), input).with_device(id));
+            self.devices.push(Kbm {
+                device,
+                file,
+                path,
+                id,
+                info,
+            });
+        }
+    }
+}
+
+/// Create an inotify watching for devices in `/dev/input/`.
+#[allow(unsafe_code)]
+fn inotify() -> Option<(Device, File)> {
+    let fd = unsafe { inotify_init1(O_NONBLOCK | O_CLOEXEC) };
+    if fd == -1 {
+        return None;
+    }
+    // Take ownership, so it gets closed on error.
+    let file = unsafe { File::from_raw_fd(fd) };
+    let mask = IN_CREATE | IN_DELETE | IN_ATTRIB;
+    if unsafe { inotify_add_watch(fd, b"/dev/input/\0".as_ptr().cast(), mask) }
+        == -1
+    {
+        return None;
+    }
+    Some((Device::new(fd, Watcher::new().input()), file))
+}
+
+/// Timestamp a device's events with the same clock as everything else.
+#[allow(unsafe_code)]
+fn set_clock(file: &File) {
+    let clock: c_int = CLOCK_MONOTONIC;
+    let _ = unsafe { ioctl(file.as_raw_fd(), EVIOCSCLOCKID, &clock) };
+}
+
+/// Take (or release) exclusive access to a device's events, so that they
+/// don't reach the desktop or other programs.
+#[allow(unsafe_code)]
+fn grab(file: &File, grab: bool) {
+    let grab = c_int::from(grab);
+    let _ = unsafe { ioctl(file.as_raw_fd(), EVIOCGRAB, grab) };
+}
+
+/// Get the range (minimum, maximum) of a device's absolute axis.
+#[allow(unsafe_code)]
+fn abs_range(file: &File, code: usize) -> Option<(i32, i32)> {
+    let mut info = AbsInfo::default();
+    let request = EVIOCGABS + code as c_ulong;
+    if unsafe { ioctl(file.as_raw_fd(), request, &mut info) } == -1 {
+        return None;
+    }
+    Some((info.minimum, info.maximum))
+}
+
+/// Get the state of a keyboard's LEDs (bitmask of `LED_*` codes).
+#[allow(unsafe_code)]
+fn leds(file: &File) -> Option<u8> {
+    let mut leds = [0u8; 2];
+    if unsafe { ioctl(file.as_raw_fd(), EVIOCGLED, leds.as_mut_ptr()) } == -1 {
+        return None;
+    }
+    Some(leds[0])
+}
+
+/// Path to the sysfs directory of an evdev device.
+fn sysfs_dir(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?;
+    Some(Path::new("/sys/class/input/").join(name).join("device/"))
+}
+
+/// Path to the sysfs capabilities of an evdev device.
+fn capabilities_dir(path: &Path) -> Option<PathBuf> {
+    Some(sysfs_dir(path)?.join("capabilities/"))
+}
+
+/// Get the name of an evdev device from sysfs.
+fn name(path: &Path) -> String {
+    sysfs_dir(path)
+        .and_then(|dir| fs::read_to_string(dir.join("name")).ok())
+        .map(|name| name.trim_end().to_string())
+        .unwrap_or_default()
+}
+
+/// Check the capabilities of an evdev device in sysfs to see if it has lock
+/// key LEDs.
+fn has_leds(path: &Path) -> bool {
+    capabilities_dir(path)
+        .is_some_and(|caps| has(&capabilities(&caps.join("ev")), EV_LED))
+}
+
+/// Check the capabilities of an evdev device in sysfs to see if it's a
+/// keyboard, mouse, touchscreen or pen tablet (and not a gamepad, which are
+/// handled by `stick`).  Touchpads report absolute positions without being
+/// direct input devices, so they aren't any of these, and are skipped.
+fn kind(path: &Path) -> Option<DeviceKind> {
+    let caps = capabilities_dir(path)?;
+    let ev = capabilities(&caps.join("ev"));
+    let key = capabilities(&caps.join("key"));
+    let rel = capabilities(&caps.join("rel"));
+    let abs = capabilities(&caps.join("abs"));
+    let props = sysfs_dir(path)
+        .map(|dir| capabilities(&dir.join("properties")))
+        .unwrap_or_default();
+
+    let gamepad = (BTN_JOYSTICK..=BTN_THUMBR).any(|code| has(&key, code));
+    let keyboard = has(&ev, EV_KEY) && has(&key, KEY_A) && has(&key, KEY_SPACE);
+    let mouse = has(&ev, EV_REL)
+        && has(&rel, REL_X)
+        && has(&rel, REL_Y)
+        && has(&key, BTN_LEFT);
+    // Touchpads are also multi-touch, but aren't direct input devices.
+    let touchscreen = has(&ev, EV_ABS)
+        && has(&abs, ABS_MT_SLOT)
+        && has(&abs, ABS_MT_POSITION_X)
+        && has(&abs, ABS_MT_POSITION_Y)
+        && has(&props, INPUT_PROP_DIRECT);
+    let pen = has(&ev, EV_ABS)
+        && has(&abs, ABS_X)
+        && has(&abs, ABS_Y)
+        && has(&key, BTN_TOOL_PEN);
+
+    if gamepad {
+        None
+    } else if keyboard {
+        Some(DeviceKind::Keyboard)
+    } else if pen {
+        Some(DeviceKind::Pen)
+    } else if mouse {
+        Some(DeviceKind::Mouse)
+    } else if touchscreen {
+        Some(DeviceKind::Touchscreen)
+    } else {
+        None
+    }
+}
+
+/// Parse a sysfs capability bitmask (space separated hexadecimal `long`s, most
+/// significant first) into a list of words, least significant first.
+fn capabilities(path: &Path) -> Vec<u64> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .split_whitespace()
+        .rev()
+        .map(|word| u64::from_str_radix(word, 16).unwrap_or(0))
+        .collect()
+}
+
+/// Check if a bit is set in a capability bitmask.
+fn has(caps: &[u64], bit: usize) -> bool {
+    let bits = c_long::BITS as usize;
+    caps.get(bit / bits)
+        .is_some_and(|word| word & (1 << (bit % bits)) != 0)
+}
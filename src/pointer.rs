@@ -0,0 +1,86 @@
+// Human
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Pointer lock, for controls (like first-person cameras) that need relative
+//! mouse motion without the pointer stopping at the edges of the window.
+//!
+//! Relative motion is always sent as
+//! [`Input::PointerDelta`](crate::Input::PointerDelta).  While the pointer is
+//! locked it's hidden and stays in place, so
+//! [`Input::PointerMove`](crate::Input::PointerMove) isn't sent.  The listener
+//! sends [`Input::PointerLock`](crate::Input::PointerLock) whenever the lock
+//! is taken or released, including when the browser releases it (for example,
+//! when Escape is pressed).
+
+#[cfg(target_os = "linux")]
+use std::{
+    sync::{Mutex, MutexGuard, PoisonError},
+    task::Waker,
+};
+
+/// Requested lock state, for the evdev listener.
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+struct Lock {
+    locked: bool,
+    // Listener to wake when the request changes.
+    waker: Option<Waker>,
+}
+
+#[cfg(target_os = "linux")]
+static LOCK: Mutex<Lock> = Mutex::new(Lock {
+    locked: false,
+    waker: None,
+});
+
+#[cfg(target_os = "linux")]
+fn state() -> MutexGuard<'static, Lock> {
+    LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Lock the pointer in place, hiding it.
+///
+/// On the web, browsers only allow this in response to a user gesture (like a
+/// click), and ignore it otherwise.  On Linux, mice are grabbed, so that they
+/// stop moving the desktop's pointer.  This does nothing for terminal input.
+pub fn lock() {
+    set(true);
+}
+
+/// Release the pointer lock, showing the pointer again.
+pub fn unlock() {
+    set(false);
+}
+
+fn set(locked: bool) {
+    #[cfg(target_arch = "wasm32")]
+    crate::web::set_pointer_lock(locked);
+
+    #[cfg(target_os = "linux")]
+    {
+        let mut state = state();
+        state.locked = locked;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    #[cfg(not(any(target_arch = "wasm32", target_os = "linux")))]
+    let _ = locked;
+}
+
+/// Get whether the pointer should be locked, and wake `waker` when that
+/// changes.
+#[cfg(target_os = "linux")]
+pub(crate) fn requested(waker: &Waker) -> bool {
+    let mut state = state();
+    state.waker = Some(waker.clone());
+    state.locked
+}
@@ -0,0 +1,446 @@
+// Human
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use std::{collections::VecDeque, time::Duration};
+
+use crate::{
+    DeviceId, Event, Input, KeyAction, KeyRepeat, KeyboardState, PointerState,
+};
+
+/// Coordinate space for the positions of the pointer, touches and pens, see
+/// [`Config::coordinates()`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Coordinates {
+    /// From 0.0 to 1.0 across each axis of the window.
+    #[default]
+    Normalized,
+    /// From 0.0 to 1.0 across the longer axis of the window, and the same
+    /// scale across the shorter axis, so that distances match in both
+    /// directions.
+    Aspect,
+    /// Logical (CSS) pixels.
+    Logical,
+    /// Physical pixels (logical pixels times the device pixel ratio).
+    Physical,
+}
+
+/// Settings for an input listener, see [`Input::listener_with()`].
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Config {
+    capacity: Option<usize>,
+    coalesce: bool,
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub(crate) terminal: bool,
+    pub(crate) repeat: Option<KeyRepeat>,
+    coordinates: Coordinates,
+}
+
+impl Config {
+    /// Default settings: an unbounded queue that keeps every event.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit how many events may wait in the queue.  When it's full, pointer
+    /// positions ([`Input::PointerMove`]) are dropped to make room, oldest
+    /// first, since each one replaces the last.  Every other event (including
+    /// relative motion and scrolling, which would be lost for good) is always
+    /// queued, even over capacity.
+    pub fn capacity(mut self, max: usize) -> Self {
+        self.capacity = Some(max);
+        self
+    }
+
+    /// Combine consecutive motion events of the same kind while they are
+    /// waiting in the queue (positions are replaced, scroll amounts and
+    /// relative motion are summed, and touch motion is combined per touch).
+    /// Motion is never combined across any other event, or motion from a
+    /// different device.
+    pub fn coalesce(mut self, coalesce: bool) -> Self {
+        self.coalesce = coalesce;
+        self
+    }
+
+    /// Read keyboard and mouse input from the terminal connected to stdin
+    /// instead of from the devices directly (Linux only).  The terminal is put
+    /// into raw mode for as long as the listener exists, so Ctrl+C is sent as a
+    /// key press rather than interrupting the program.  If stdin isn't a
+    /// terminal, the listener prints why to stderr, and only sends controller
+    /// events.
+    pub fn terminal(mut self, terminal: bool) -> Self {
+        self.terminal = terminal;
+        self
+    }
+
+    /// Repeat held keys in software after `delay`, `rate` times per second,
+    /// instead of passing on the operating system's (or terminal's) repeats,
+    /// so that repeat speed is the same everywhere.  Only key events are
+    /// repeated; text input still repeats at the operating system's speed.
+    pub fn repeat(mut self, delay: Duration, rate: f32) -> Self {
+        self.repeat = Some(KeyRepeat::new(delay, rate));
+        self
+    }
+
+    /// Choose the coordinate space for the positions of the pointer, touches
+    /// and pens (normalized by default).  Without a window, evdev devices are
+    /// mapped onto a virtual 1920x1080 screen, and terminals use their size
+    /// in pixels (or in cells, if the terminal doesn't report pixels).
+    pub fn coordinates(mut self, coordinates: Coordinates) -> Self {
+        self.coordinates = coordinates;
+        self
+    }
+}
+
+/// FIFO queue of input events waiting to be returned by the listener.
+#[derive(Debug)]
+pub(crate) struct Queue {
+    events: VecDeque<Event>,
+    config: Config,
+    // Logical width and height of the window, and the device pixel ratio.
+    surface: (f32, f32, f32),
+    // Keys and buttons held down, to release on focus loss, and the devices
+    // that last sent keys and clicks.
+    keyboard: KeyboardState,
+    pointer: PointerState,
+    devices: (Option<DeviceId>, Option<DeviceId>),
+}
+
+impl Queue {
+    pub(crate) const fn new() -> Self {
+        Self {
+            events: VecDeque::new(),
+            config: Config {
+                capacity: None,
+                coalesce: false,
+                terminal: false,
+                repeat: None,
+                coordinates: Coordinates::Normalized,
+            },
+            surface: (1.0, 1.0, 1.0),
+            keyboard: KeyboardState::new(),
+            pointer: PointerState::new(),
+            devices: (None, None),
+        }
+    }
+
+    pub(crate) fn configure(&mut self, config: Config) {
+        self.config = config;
+    }
+
+    /// Set the size of the window in logical pixels, and the device pixel
+    /// ratio, for converting positions out of normalized coordinates.
+    pub(crate) fn set_surface(&mut self, width: f32, height: f32, scale: f32) {
+        self.surface = (width, height, scale);
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+
+    pub(crate) fn push(&mut self, mut event: Event) {
+        self.track(&event);
+        self.place(event.input_mut());
+
+        if self.config.coalesce {
+            // Only merge into the latest event, so that order is kept.
+            if let Some(queued) = self.events.back_mut() {
+                if queued.device() == event.device()
+                    && merge(queued.input_mut(), event.input())
+                {
+                    queued.set_time(event.time());
+                    return;
+                }
+            }
+        }
+
+        if let Some(capacity) = self.config.capacity {
+            if self.events.len() >= capacity {
+                let mut queued = self.events.iter().map(Event::input);
+                if let Some(i) = queued.position(is_position) {
+                    let _ = self.events.remove(i);
+                } else if is_position(event.input()) {
+                    return;
+                }
+            }
+        }
+
+        self.events.push_back(event);
+    }
+
+    /// Keep track of which keys and buttons are held down, releasing them all
+    /// when focus is lost.
+    fn track(&mut self, event: &Event) {
+        match event.input() {
+            Input::Key(..) => self.devices.0 = event.device(),
+            Input::Click(..) => self.devices.1 = event.device(),
+            Input::FocusLost => {
+                let time = event.time();
+                let mods = self.keyboard.mods();
+                let keys: Vec<_> = self.keyboard.pressed_keys().collect();
+                for key in keys {
+                    let input = Input::Key(mods, key, KeyAction::Release);
+                    self.release(time, self.devices.0, input);
+                }
+                let mods = self.pointer.mods();
+                let btns: Vec<_> = self.pointer.pressed_buttons().collect();
+                for btn in btns {
+                    let input = Input::Click(mods, btn, false);
+                    self.release(time, self.devices.1, input);
+                }
+            }
+            _ => {}
+        }
+        self.keyboard.update(event.input());
+        self.keyboard.next_frame();
+        self.pointer.update(event.input());
+        self.pointer.next_frame();
+    }
+
+    /// Queue a release that's sent because focus was lost.
+    fn release(
+        &mut self,
+        time: Duration,
+        device: Option<DeviceId>,
+        input: Input,
+    ) {
+        let event = Event::new(time, input);
+        self.push(match device {
+            Some(device) => event.with_device(device),
+            None => event,
+        });
+    }
+
+    /// Convert a position sent by a backend (in normalized coordinates) into
+    /// the configured coordinate space.
+    fn place(&self, input: &mut Input) {
+        let (width, height, scale) = self.surface;
+        let (sx, sy) = match self.config.coordinates {
+            Coordinates::Normalized => return,
+            Coordinates::Aspect => {
+                let side = width.max(height);
+                if side <= 0.0 {
+                    return;
+                }
+                (width / side, height / side)
+            }
+            Coordinates::Logical => (width, height),
+            Coordinates::Physical => (width * scale, height * scale),
+        };
+        match input {
+            Input::PointerMove { x, y } => {
+                *x *= sx;
+                *y *= sy;
+            }
+            Input::TouchStart(touch)
+            | Input::TouchMove(touch)
+            | Input::TouchEnd(touch)
+            | Input::TouchCancel(touch) => *touch = touch.scaled(sx, sy),
+            Input::Pen(pen) => *pen = pen.scaled(sx, sy),
+            _ => {}
+        }
+    }
+}
+
+/// Check if an event is a pointer position, which can be dropped because the
+/// next one replaces it.
+fn is_position(input: &Input) -> bool {
+    matches!(input, Input::PointerMove { .. })
+}
+
+/// Try to merge `new` into `queued`, returning true on success.
+fn merge(queued: &mut Input, new: &Input) -> bool {
+    use Input::*;
+
+    match (queued, new) {
+        (PointerMove { x, y }, PointerMove { x: new_x, y: new_y }) => {
+            *x = *new_x;
+            *y = *new_y;
+        }
+        (PointerDelta { dx, dy }, PointerDelta { dx: new_x, dy: new_y }) => {
+            *dx += *new_x;
+            *dy += *new_y;
+        }
+        (PinchW(old), PinchW(new))
+        | (PinchH(old), PinchH(new))
+        | (PinchZ(old), PinchZ(new)) => *old = *new,
+        (TouchMove(old), TouchMove(new)) if old.id() == new.id() => {
+            *old = *new
+        }
+        (Scroll(old_mods, old), Scroll(mods, new))
+            if old_mods == mods && old.source() == new.source() =>
+        {
+            old.merge(new)
+        }
+        _ => return false,
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Btn, DeviceId, Key, Mod, Scroll, ScrollSource, Touch};
+
+    fn queue(config: Config) -> Queue {
+        let mut queue = Queue::new();
+        queue.configure(config);
+        queue
+    }
+
+    fn event(input: Input) -> Event {
+        Event::new(Duration::default(), input).with_device(DeviceId(0))
+    }
+
+    fn moved(x: f32) -> Event {
+        event(Input::PointerMove { x, y: 0.0 })
+    }
+
+    fn inputs(queue: &mut Queue) -> Vec<Input> {
+        std::iter::from_fn(|| queue.pop()).map(Event::into_input).collect()
+    }
+
+    #[test]
+    fn capacity_keeps_presses() {
+        let mut queue = queue(Config::new().capacity(2));
+        queue.push(event(Input::Key(Mod::new(), Key::A, KeyAction::Press)));
+        queue.push(event(Input::Click(Mod::new(), Btn::Left, true)));
+        queue.push(event(Input::Text("a".to_string())));
+        let inputs = inputs(&mut queue);
+
+        assert_eq!(inputs.len(), 3);
+        assert!(matches!(inputs[0], Input::Key(_, Key::A, _)));
+        assert!(matches!(inputs[1], Input::Click(_, Btn::Left, true)));
+        assert!(matches!(inputs[2], Input::Text(ref text) if text == "a"));
+    }
+
+    #[test]
+    fn capacity_drops_oldest_motion() {
+        let mut queue = queue(Config::new().capacity(3));
+        queue.push(moved(0.1));
+        queue.push(event(Input::Click(Mod::new(), Btn::Left, true)));
+        queue.push(moved(0.2));
+        queue.push(moved(0.3));
+        let inputs = inputs(&mut queue);
+
+        assert_eq!(inputs.len(), 3);
+        assert!(matches!(inputs[0], Input::Click(..)));
+        assert!(matches!(inputs[1], Input::PointerMove { x, .. } if x == 0.2));
+        assert!(matches!(inputs[2], Input::PointerMove { x, .. } if x == 0.3));
+    }
+
+    #[test]
+    fn capacity_drops_incoming_motion() {
+        let mut queue = queue(Config::new().capacity(1));
+        queue.push(event(Input::Click(Mod::new(), Btn::Left, true)));
+        queue.push(moved(0.1));
+        let inputs = inputs(&mut queue);
+
+        assert_eq!(inputs.len(), 1);
+        assert!(matches!(inputs[0], Input::Click(..)));
+    }
+
+    #[test]
+    fn capacity_keeps_relative_motion() {
+        let mut queue = queue(Config::new().capacity(1));
+        let scroll = Scroll::new(ScrollSource::Wheel, 0.0, 48.0);
+        queue.push(event(Input::PointerDelta { dx: 1.0, dy: 2.0 }));
+        queue.push(event(Input::Scroll(Mod::new(), scroll)));
+        queue.push(moved(0.1));
+        let inputs = inputs(&mut queue);
+
+        assert_eq!(inputs.len(), 2);
+        assert!(matches!(inputs[0], Input::PointerDelta { .. }));
+        assert!(matches!(inputs[1], Input::Scroll(..)));
+    }
+
+    #[test]
+    fn coalesce_positions() {
+        let mut queue = queue(Config::new().coalesce(true));
+        queue.push(moved(0.1));
+        queue.push(moved(0.2));
+        let inputs = inputs(&mut queue);
+
+        assert_eq!(inputs.len(), 1);
+        assert!(matches!(inputs[0], Input::PointerMove { x, .. } if x == 0.2));
+    }
+
+    #[test]
+    fn coalesce_sums() {
+        let mut queue = queue(Config::new().coalesce(true));
+        queue.push(event(Input::PointerDelta { dx: 1.0, dy: 2.0 }));
+        queue.push(event(Input::PointerDelta { dx: 3.0, dy: 4.0 }));
+        let scroll = Scroll::new(ScrollSource::Wheel, 0.0, 48.0);
+        queue.push(event(Input::Scroll(Mod::new(), scroll.with_detents(0, 1))));
+        queue.push(event(Input::Scroll(Mod::new(), scroll.with_detents(0, 1))));
+        let inputs = inputs(&mut queue);
+
+        assert_eq!(inputs.len(), 2);
+        assert!(matches!(
+            inputs[0],
+            Input::PointerDelta { dx, dy } if dx == 4.0 && dy == 6.0
+        ));
+        assert!(matches!(
+            inputs[1],
+            Input::Scroll(_, s)
+                if s.delta() == (0.0, 96.0) && s.detents() == (0, 2)
+        ));
+    }
+
+    #[test]
+    fn coalesce_touches() {
+        let mut queue = queue(Config::new().coalesce(true));
+        let touch = |id, x| event(Input::TouchMove(Touch::new(id, x, 0.0, 0.5)));
+        queue.push(touch(1, 0.1));
+        queue.push(touch(1, 0.2));
+        queue.push(touch(2, 0.3));
+        let inputs = inputs(&mut queue);
+
+        assert_eq!(inputs.len(), 2);
+        assert!(matches!(
+            inputs[0],
+            Input::TouchMove(t) if t.id() == 1 && t.x() == 0.2
+        ));
+        assert!(matches!(
+            inputs[1],
+            Input::TouchMove(t) if t.id() == 2 && t.x() == 0.3
+        ));
+    }
+
+    #[test]
+    fn coalesce_keeps_order() {
+        let mut queue = queue(Config::new().coalesce(true));
+        queue.push(moved(0.1));
+        queue.push(moved(0.2).with_device(DeviceId(1)));
+        queue.push(event(Input::PointerDelta { dx: 1.0, dy: 0.0 }));
+        queue.push(moved(0.3));
+        let inputs = inputs(&mut queue);
+
+        // Not merged across devices, or across other events.
+        assert_eq!(inputs.len(), 4);
+        assert!(matches!(inputs[0], Input::PointerMove { x, .. } if x == 0.1));
+        assert!(matches!(inputs[1], Input::PointerMove { x, .. } if x == 0.2));
+        assert!(matches!(inputs[2], Input::PointerDelta { .. }));
+        assert!(matches!(inputs[3], Input::PointerMove { x, .. } if x == 0.3));
+    }
+
+    #[test]
+    fn focus_lost_releases() {
+        let mut queue = queue(Config::new());
+        queue.push(event(Input::Key(Mod::new(), Key::A, KeyAction::Press)));
+        queue.push(event(Input::Click(Mod::new(), Btn::Left, true)));
+        queue.push(event(Input::FocusLost));
+        let inputs = inputs(&mut queue);
+
+        assert_eq!(inputs.len(), 5);
+        assert!(matches!(inputs[2], Input::Key(_, Key::A, KeyAction::Release)));
+        assert!(matches!(inputs[3], Input::Click(_, Btn::Left, false)));
+        assert!(matches!(inputs[4], Input::FocusLost));
+    }
+}
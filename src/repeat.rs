@@ -0,0 +1,125 @@
+// Human
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use std::time::Duration;
+
+use crate::{DeviceId, Event, Input, Key, KeyAction, Mod};
+
+/// The key being repeated.
+#[derive(Debug, Copy, Clone)]
+struct Held {
+    mods: Mod,
+    key: Key,
+    device: Option<DeviceId>,
+    // When the next repeat is due.
+    next: Duration,
+}
+
+/// Software key repeat, so that held keys repeat at the same speed on every
+/// backend (see [`Config::repeat()`](crate::Config::repeat) to have the
+/// listener do this).
+///
+/// Like the operating system's key repeat, only the most recently pressed key
+/// repeats, and modifier and lock keys never do.
+#[derive(Debug, Copy, Clone)]
+pub struct KeyRepeat {
+    delay: Duration,
+    interval: Duration,
+    held: Option<Held>,
+}
+
+impl KeyRepeat {
+    /// Repeat keys that are held down for `delay`, `rate` times per second.
+    pub fn new(delay: Duration, rate: f32) -> Self {
+        Self {
+            delay,
+            interval: Duration::try_from_secs_f32(rate.recip())
+                .unwrap_or(Duration::MAX),
+            held: None,
+        }
+    }
+
+    /// Pass an event through, returning `None` if it should be dropped
+    /// (repeats that don't come from this generator).
+    pub fn filter(&mut self, event: Event) -> Option<Event> {
+        match *event.input() {
+            Input::Key(_, _, KeyAction::Repeat) => return None,
+            Input::Key(mods, key, KeyAction::Press) if !is_modifier(key) => {
+                self.held = Some(Held {
+                    mods,
+                    key,
+                    device: event.device(),
+                    next: event.time().saturating_add(self.delay),
+                });
+            }
+            Input::Key(mods, key, action) => {
+                if let Some(ref mut held) = self.held {
+                    if action == KeyAction::Release && held.key == key {
+                        self.held = None;
+                    } else {
+                        // Repeat with the new modifiers, like shift held down
+                        // after a letter.
+                        held.mods = mods;
+                    }
+                }
+            }
+            Input::FocusLost => self.held = None,
+            Input::DeviceRemoved(ref device)
+                if self.held.is_some_and(|h| h.device == Some(device.id())) =>
+            {
+                self.held = None;
+            }
+            _ => {}
+        }
+        Some(event)
+    }
+
+    /// Get when the next repeat is due, if a key is held down.
+    pub fn deadline(&self) -> Option<Duration> {
+        self.held.map(|held| held.next)
+    }
+
+    /// Get the next repeat, if it's due at time `now` (in the same time base as
+    /// [`Event::time()`]).  Repeats that were missed because this wasn't
+    /// called for a while are skipped.
+    pub fn repeat(&mut self, now: Duration) -> Option<Event> {
+        let held = self.held.as_mut()?;
+        if now < held.next {
+            return None;
+        }
+        let input = Input::Key(held.mods, held.key, KeyAction::Repeat);
+        let mut event = Event::new(held.next, input);
+        if let Some(device) = held.device {
+            event = event.with_device(device);
+        }
+        held.next = held.next.saturating_add(self.interval);
+        if held.next < now {
+            held.next = now.saturating_add(self.interval);
+        }
+        Some(event)
+    }
+}
+
+/// Check if a key is a modifier or lock key, which don't repeat.
+fn is_modifier(key: Key) -> bool {
+    matches!(
+        key,
+        Key::LShift
+            | Key::RShift
+            | Key::LCtrl
+            | Key::RCtrl
+            | Key::LAlt
+            | Key::RAlt
+            | Key::Thumb
+            | Key::Caps
+            | Key::Num
+            | Key::Scroll
+    )
+}
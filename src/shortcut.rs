@@ -0,0 +1,127 @@
+// Human
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use std::{
+    error::Error,
+    fmt::{Display, Formatter, Result},
+    str::FromStr,
+};
+
+use crate::{Btn, Input, Key, KeyAction, Mod};
+
+/// A key or mouse button, along with the modifiers that must be held down.
+///
+/// Shortcuts are written as modifiers followed by the key or button, separated
+/// by `+`, for example `"Ctrl+Shift+P"` or `"Alt+Btn:Middle"`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Shortcut {
+    /// A key on a keyboard.
+    Key(Mod, Key),
+    /// A button on a mouse.
+    Btn(Mod, Btn),
+}
+
+impl Shortcut {
+    /// Get the modifiers that must be held down.
+    pub fn mods(self) -> Mod {
+        match self {
+            Shortcut::Key(mods, _) | Shortcut::Btn(mods, _) => mods,
+        }
+    }
+
+    /// Check if an input is this shortcut being pressed (with exactly these
+    /// modifiers held down).  Lock keys only need to be on if they are part of
+    /// the shortcut.
+    pub fn matches(self, input: &Input) -> bool {
+        let (mods, found) = match (self, input) {
+            (Shortcut::Key(_, key), Input::Key(m, k, KeyAction::Press)) => {
+                (*m, key == *k)
+            }
+            (Shortcut::Btn(_, btn), Input::Click(m, b, true)) => {
+                (*m, btn == *b)
+            }
+            _ => return false,
+        };
+        let want = self.mods();
+        found && mods.held() == want.held() && mods.contains(want)
+    }
+}
+
+impl Display for Shortcut {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.mods() != Mod::new() {
+            write!(f, "{}+", self.mods())?;
+        }
+        match self {
+            Shortcut::Key(_, key) => write!(f, "{}", key),
+            Shortcut::Btn(_, btn) => write!(f, "Btn:{}", btn),
+        }
+    }
+}
+
+impl FromStr for Shortcut {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, ParseError> {
+        let (mods, last) = match s.rfind('+') {
+            Some(i) => (s[..i].parse()?, &s[i + 1..]),
+            None => (Mod::new(), s),
+        };
+        let last = last.trim();
+        if last.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        if last.parse::<Mod>().is_ok() {
+            return Err(ParseError::MissingKey);
+        }
+        match last.get(..4) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("Btn:") => {
+                Ok(Shortcut::Btn(mods, last[4..].parse()?))
+            }
+            _ => Ok(Shortcut::Key(mods, last.parse()?)),
+        }
+    }
+}
+
+/// Error from parsing a [`Key`], [`Btn`], [`Mod`] or [`Shortcut`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// Nothing between `+` separators, or at the end.
+    Empty,
+    /// Not the name of a key.
+    UnknownKey(String),
+    /// Not the name of a mouse button.
+    UnknownBtn(String),
+    /// Not the name of a modifier.
+    UnknownMod(String),
+    /// A shortcut that's only modifiers.
+    MissingKey,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ParseError::Empty => write!(f, "empty key, button or modifier"),
+            ParseError::UnknownKey(name) => write!(f, "unknown key `{}`", name),
+            ParseError::UnknownBtn(name) => {
+                write!(f, "unknown mouse button `{}`", name)
+            }
+            ParseError::UnknownMod(name) => {
+                write!(f, "unknown modifier `{}`", name)
+            }
+            ParseError::MissingKey => {
+                write!(f, "missing key or button after modifiers")
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
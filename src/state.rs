@@ -0,0 +1,212 @@
+// Human
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{Btn, Input, Key, Mod};
+
+/// Set of keys, indexed by `Key as u8`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+struct KeySet([u64; 4]);
+
+impl KeySet {
+    fn contains(&self, key: Key) -> bool {
+        self.0[key as usize / 64] & 1 << (key as usize % 64) != 0
+    }
+
+    fn set(&mut self, key: Key, value: bool) {
+        let bit = 1 << (key as usize % 64);
+        if value {
+            self.0[key as usize / 64] |= bit;
+        } else {
+            self.0[key as usize / 64] &= !bit;
+        }
+    }
+}
+
+/// Get the modifiers from an input event, if it has any.
+fn mods(input: &Input) -> Option<Mod> {
+    match *input {
+        Input::Key(mods, _, _)
+        | Input::Click(mods, _, _)
+        | Input::Scroll(mods, _) => Some(mods),
+        _ => None,
+    }
+}
+
+/// Keyboard state, for game loops that poll which keys are held rather than
+/// reacting to each event.
+///
+/// Feed every [`Input`] to [`update()`](KeyboardState::update), and call
+/// [`next_frame()`](KeyboardState::next_frame) once per frame after checking
+/// the state.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct KeyboardState {
+    pressed: KeySet,
+    just_pressed: KeySet,
+    just_released: KeySet,
+    mods: Mod,
+}
+
+impl KeyboardState {
+    /// Create a keyboard state with no keys held down.
+    pub const fn new() -> Self {
+        Self {
+            pressed: KeySet([0; 4]),
+            just_pressed: KeySet([0; 4]),
+            just_released: KeySet([0; 4]),
+            mods: Mod::new(),
+        }
+    }
+
+    /// Update the state from an input event (events that aren't from the
+    /// keyboard only update the modifiers).
+    pub fn update(&mut self, input: &Input) {
+        if let Some(mods) = mods(input) {
+            self.mods = mods;
+        }
+        if let Input::Key(_, key, action) = *input {
+            let pressed = action.is_down();
+            if pressed && !self.pressed.contains(key) {
+                self.just_pressed.set(key, true);
+            } else if !pressed && self.pressed.contains(key) {
+                self.just_released.set(key, true);
+            }
+            self.pressed.set(key, pressed);
+        }
+    }
+
+    /// Start a new frame, forgetting which keys were just pressed or released.
+    pub fn next_frame(&mut self) {
+        self.just_pressed = KeySet::default();
+        self.just_released = KeySet::default();
+    }
+
+    /// Check if a key is held down.
+    pub fn is_pressed(&self, key: Key) -> bool {
+        self.pressed.contains(key)
+    }
+
+    /// Check if a key was pressed this frame (it may have been released again
+    /// since).
+    pub fn just_pressed(&self, key: Key) -> bool {
+        self.just_pressed.contains(key)
+    }
+
+    /// Check if a key was released this frame (it may have been pressed again
+    /// since).
+    pub fn just_released(&self, key: Key) -> bool {
+        self.just_released.contains(key)
+    }
+
+    /// Iterate over the keys that are held down.
+    pub fn pressed_keys(&self) -> impl Iterator<Item = Key> + '_ {
+        Key::all().filter(move |key| self.pressed.contains(*key))
+    }
+
+    /// Get the modifiers, as of the latest event.
+    pub fn mods(&self) -> Mod {
+        self.mods
+    }
+}
+
+/// Pointer state, for game loops that poll which mouse buttons are held and
+/// where the pointer is rather than reacting to each event.
+///
+/// Feed every [`Input`] to [`update()`](PointerState::update), and call
+/// [`next_frame()`](PointerState::next_frame) once per frame after checking
+/// the state.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct PointerState {
+    pressed: u8,
+    just_pressed: u8,
+    just_released: u8,
+    position: Option<(f32, f32)>,
+    mods: Mod,
+}
+
+impl PointerState {
+    /// Create a pointer state with no buttons held down, and an unknown
+    /// position.
+    pub const fn new() -> Self {
+        Self {
+            pressed: 0,
+            just_pressed: 0,
+            just_released: 0,
+            position: None,
+            mods: Mod::new(),
+        }
+    }
+
+    /// Update the state from an input event (events that aren't from the
+    /// pointer only update the modifiers).
+    pub fn update(&mut self, input: &Input) {
+        if let Some(mods) = mods(input) {
+            self.mods = mods;
+        }
+        match *input {
+            Input::Click(_, btn, pressed) => {
+                let bit = 1 << btn as u8;
+                if pressed && self.pressed & bit == 0 {
+                    self.just_pressed |= bit;
+                } else if !pressed && self.pressed & bit != 0 {
+                    self.just_released |= bit;
+                }
+                if pressed {
+                    self.pressed |= bit;
+                } else {
+                    self.pressed &= !bit;
+                }
+            }
+            Input::PointerMove { x, y } => self.position = Some((x, y)),
+            Input::PointerLeave => self.position = None,
+            _ => {}
+        }
+    }
+
+    /// Start a new frame, forgetting which buttons were just pressed or
+    /// released.
+    pub fn next_frame(&mut self) {
+        self.just_pressed = 0;
+        self.just_released = 0;
+    }
+
+    /// Check if a button is held down.
+    pub fn is_pressed(&self, btn: Btn) -> bool {
+        self.pressed & 1 << btn as u8 != 0
+    }
+
+    /// Check if a button was pressed this frame (it may have been released
+    /// again since).
+    pub fn just_pressed(&self, btn: Btn) -> bool {
+        self.just_pressed & 1 << btn as u8 != 0
+    }
+
+    /// Check if a button was released this frame (it may have been pressed
+    /// again since).
+    pub fn just_released(&self, btn: Btn) -> bool {
+        self.just_released & 1 << btn as u8 != 0
+    }
+
+    /// Iterate over the buttons that are held down.
+    pub fn pressed_buttons(&self) -> impl Iterator<Item = Btn> + '_ {
+        Btn::all().filter(move |btn| self.is_pressed(*btn))
+    }
+
+    /// Get the position of the pointer (same coordinates as
+    /// [`Input::PointerMove`]), or `None` if it hasn't moved yet or has left
+    /// the window.
+    pub fn position(&self) -> Option<(f32, f32)> {
+        self.position
+    }
+
+    /// Get the modifiers, as of the latest event.
+    pub fn mods(&self) -> Mod {
+        self.mods
+    }
+}
@@ -0,0 +1,848 @@
+// Human
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Parsing of terminal (ANSI / xterm) input byte streams into `Input`s.
+
+use std::collections::VecDeque;
+
+use crate::{clipboard, Btn, Input, Key, KeyAction, Mod, Scroll, ScrollSource};
+
+const ESC: u8 = 0x1B;
+const BEL: u8 = 0x07;
+
+/// End of a bracketed paste.
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+/// Pixels scrolled by one detent of a mouse wheel; three lines, using the same
+/// line height as the web backend.
+const SCROLL_DETENT: f32 = 3.0 * 16.0;
+
+/// Longest escape sequence that will be buffered before giving up on it.
+const MAX_SEQUENCE: usize = 64;
+
+/// Kitty keyboard protocol code for `ISO_Level3_Shift` (AltGr).
+const KITTY_ALTGR: u32 = 57453;
+
+/// Parser for bytes read from a terminal in raw mode.
+///
+/// Understands UTF-8 text, control characters, xterm-style escape sequences
+/// for special keys (with modifiers), SGR mouse reports, bracketed paste, and
+/// focus in/out reports.  Since legacy terminal input doesn't report key
+/// releases, each key is sent as a press immediately followed by a release,
+/// unless the kitty keyboard protocol is enabled (see
+/// [`TermParser::set_enhanced()`]).
+#[derive(Debug)]
+pub struct TermParser {
+    // Bytes not yet parsed (an incomplete sequence or character).
+    buf: Vec<u8>,
+    // Parsed events.
+    queue: VecDeque<Input>,
+    // Characters parsed in a row, sent once something else is parsed.
+    run: String,
+    // Text pasted so far, while inside a bracketed paste.
+    paste: Option<String>,
+    // Clipboard reply so far, while inside an OSC 52 sequence.
+    osc: Option<Vec<u8>>,
+    // Text from the latest clipboard reply.
+    clipboard: Option<String>,
+    // Number of device attributes replies not yet taken.
+    attributes: usize,
+    // Size of the terminal (columns, rows).
+    size: (u16, u16),
+    // Last reported pointer cell.
+    pointer: (u16, u16),
+    // Kitty keyboard protocol support, once the terminal has replied.
+    kitty: Option<bool>,
+    // True if kitty keyboard protocol enhancements are enabled.
+    enhanced: bool,
+    // True while AltGr is held (only reported by the kitty keyboard protocol).
+    altgr: bool,
+}
+
+impl Default for TermParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TermParser {
+    /// Create a new parser, for a terminal that's 80 columns by 24 rows.
+    pub fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            queue: VecDeque::new(),
+            run: String::new(),
+            paste: None,
+            osc: None,
+            clipboard: None,
+            attributes: 0,
+            size: (80, 24),
+            pointer: (0, 0),
+            kitty: None,
+            enhanced: false,
+            altgr: false,
+        }
+    }
+
+    /// Set the size of the terminal in columns and rows, used to convert
+    /// mouse reports into pointer coordinates.
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.size = (cols.max(1), rows.max(1));
+    }
+
+    /// Feed bytes read from the terminal.  Sequences may be split across
+    /// calls.
+    ///
+    /// A single typed character is sent as a key press, its text and a key
+    /// release.  Several characters in a row (like text committed by an input
+    /// method) are sent as a press and release for each one's key, followed by
+    /// all of the text in one [`Input::Text`].
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+        self.parse();
+    }
+
+    /// Signal that no more bytes are coming for now, so that a pending lone
+    /// Escape byte is sent as the Escape key (and Alt + ] as a key, not the
+    /// start of a clipboard reply) rather than waiting for the rest of a
+    /// sequence that isn't coming.
+    ///
+    /// Terminals may split a sequence across reads, so only call this once
+    /// no bytes have arrived for a short time (see [`TermParser::pending()`]).
+    pub fn flush(&mut self) {
+        if !self.pending() {
+            return;
+        }
+        // Alt + ] looks like the start of a clipboard reply.
+        if self.buf.get(1) == Some(&b']') {
+            let _ = self.buf.drain(..2);
+            if let Some((key, mods)) = printable(']') {
+                self.key(mods.add_alt(), key);
+            }
+        } else {
+            let _ = self.buf.remove(0);
+            self.key(Mod::new(), Key::Esc);
+        }
+        self.parse();
+    }
+
+    /// Check if an Escape byte is waiting for the rest of its sequence, and
+    /// will be sent as the Escape key by [`TermParser::flush()`] if it doesn't
+    /// arrive.
+    pub fn pending(&self) -> bool {
+        let sequence = self.paste.is_some() || self.osc.is_some();
+        !sequence && self.buf.first() == Some(&ESC)
+    }
+
+    /// Whether the terminal supports the [kitty keyboard protocol], once it
+    /// has replied to a query (`CSI ? u` followed by `CSI c`, so that
+    /// terminals without support reply too).
+    ///
+    /// [kitty keyboard protocol]: https://sw.kovidgoyal.net/kitty/keyboard-protocol/
+    pub fn kitty(&self) -> Option<bool> {
+        self.kitty
+    }
+
+    /// Set whether the kitty keyboard protocol's enhancements have been
+    /// enabled in the terminal.  When they are, key presses, repeats and
+    /// releases come from the terminal instead of each key being sent as a
+    /// press immediately followed by a release.
+    pub fn set_enhanced(&mut self, enhanced: bool) {
+        self.enhanced = enhanced;
+    }
+
+    /// Take the text from the terminal's latest reply to an OSC 52 clipboard
+    /// query (`ESC ] 52 ; c ; ? BEL`).
+    pub fn clipboard(&mut self) -> Option<String> {
+        self.clipboard.take()
+    }
+
+    /// Take the number of replies to primary device attributes requests
+    /// (`CSI c`) since this was last called.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub(crate) fn attributes(&mut self) -> usize {
+        std::mem::take(&mut self.attributes)
+    }
+
+    /// Take the next parsed event.
+    pub fn pop(&mut self) -> Option<Input> {
+        self.queue.pop_front()
+    }
+
+    /// Parse as much of the buffer as possible.
+    fn parse(&mut self) {
+        let mut buf = std::mem::take(&mut self.buf);
+        let mut start = 0;
+        while start < buf.len() {
+            let rest = &buf[start..];
+            let used = if self.paste.is_some() {
+                self.paste_text(rest)
+            } else if self.osc.is_some() {
+                self.osc_text(rest)
+            } else {
+                self.token(rest)
+            };
+            match used {
+                Some(used) => start += used,
+                None if rest.len() > MAX_SEQUENCE => start += 1,
+                None => break,
+            }
+        }
+        let _ = buf.drain(..start);
+        self.buf = buf;
+        self.end_run();
+    }
+
+    /// Parse one token, returning how many bytes it used (`None` if
+    /// incomplete).
+    fn token(&mut self, bytes: &[u8]) -> Option<usize> {
+        match bytes[0] {
+            ESC => {
+                self.end_run();
+                self.escape(bytes)
+            }
+            byte @ (0x00..=0x1F | 0x7F) => {
+                self.end_run();
+                if let Some((key, mods)) = control(byte) {
+                    self.key(mods, key);
+                }
+                Some(1)
+            }
+            _ => {
+                let (c, used) = utf8(bytes)?;
+                if let Some(c) = c {
+                    self.run.push(c);
+                }
+                Some(used)
+            }
+        }
+    }
+
+    /// Parse a sequence starting with an escape byte.
+    fn escape(&mut self, bytes: &[u8]) -> Option<usize> {
+        match *bytes.get(1)? {
+            b'[' => {
+                let end = bytes[2..]
+                    .iter()
+                    .position(|b| (0x40..=0x7E).contains(b))?
+                    + 2;
+                let params = String::from_utf8_lossy(&bytes[2..end]);
+                if params.is_empty() && bytes[end] == b'M' {
+                    // Legacy (X10) mouse report, 3 raw bytes follow.
+                    let report = bytes.get(end + 1..end + 4)?;
+                    self.legacy_mouse(report);
+                    return Some(end + 4);
+                }
+                self.csi(&params, bytes[end]);
+                Some(end + 1)
+            }
+            // Clipboard reply (OSC 52), or Alt + ]
+            b']' => match bytes.get(2..5) {
+                Some(b"52;") => {
+                    self.osc = Some(Vec::new());
+                    Some(5)
+                }
+                None if b"52;".starts_with(&bytes[2..]) => None,
+                _ => {
+                    if let Some((key, mods)) = printable(']') {
+                        self.key(mods.add_alt(), key);
+                    }
+                    Some(2)
+                }
+            },
+            b'O' => {
+                let key = match *bytes.get(2)? {
+                    b'A' => Some(Key::Up),
+                    b'B' => Some(Key::Down),
+                    b'C' => Some(Key::Right),
+                    b'D' => Some(Key::Left),
+                    b'H' => Some(Key::Home),
+                    b'F' => Some(Key::End),
+                    b'E' => Some(Key::NumFive),
+                    // Application keypad mode
+                    b'M' => Some(Key::NumEnter),
+                    b'X' => Some(Key::NumEqual),
+                    b'j' => Some(Key::NumStar),
+                    b'k' => Some(Key::NumPlus),
+                    b'l' => Some(Key::NumComma),
+                    b'm' => Some(Key::NumMinus),
+                    b'n' => Some(Key::NumPeriod),
+                    b'o' => Some(Key::NumSlash),
+                    b'p' => Some(Key::NumZero),
+                    b'q' => Some(Key::NumOne),
+                    b'r' => Some(Key::NumTwo),
+                    b's' => Some(Key::NumThree),
+                    b't' => Some(Key::NumFour),
+                    b'u' => Some(Key::NumFive),
+                    b'v' => Some(Key::NumSix),
+                    b'w' => Some(Key::NumSeven),
+                    b'x' => Some(Key::NumEight),
+                    b'y' => Some(Key::NumNine),
+                    b'P' => Some(Key::F1),
+                    b'Q' => Some(Key::F2),
+                    b'R' => Some(Key::F3),
+                    b'S' => Some(Key::F4),
+                    _ => None,
+                };
+                if let Some(key) = key {
+                    self.key(Mod::new(), key);
+                }
+                Some(3)
+            }
+            ESC => {
+                self.key(Mod::new(), Key::Esc);
+                Some(1)
+            }
+            // Alt + key
+            byte @ (0x00..=0x1F | 0x7F) => {
+                if let Some((key, mods)) = control(byte) {
+                    self.key(mods.add_alt(), key);
+                }
+                Some(2)
+            }
+            byte if byte.is_ascii() => {
+                if let Some((key, mods)) = printable(byte.into()) {
+                    self.key(mods.add_alt(), key);
+                }
+                Some(2)
+            }
+            _ => {
+                self.key(Mod::new(), Key::Esc);
+                Some(1)
+            }
+        }
+    }
+
+    /// Handle a control sequence.
+    fn csi(&mut self, params: &str, end: u8) {
+        // Replies to queries
+        if params.starts_with('?') {
+            match end {
+                b'u' => self.kitty = Some(true),
+                b'c' => {
+                    self.attributes += 1;
+                    if self.kitty.is_none() {
+                        self.kitty = Some(false);
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+        if let Some(params) = params.strip_prefix('<') {
+            if end == b'M' || end == b'm' {
+                self.sgr_mouse(params, end == b'M');
+            }
+            return;
+        }
+
+        // `code:shifted:base;modifiers:event;text`
+        let mut params = params.split(';');
+        let mut codes = params.next().unwrap_or("").split(':');
+        let code: u32 = codes.next().and_then(|p| p.parse().ok()).unwrap_or(1);
+        let shifted = codes.next().and_then(|p| p.parse().ok());
+        let base = codes.next().and_then(|p| p.parse().ok());
+        let mut state = params.next().unwrap_or("").split(':');
+        let mut mods = modifiers(state.next());
+        let event = state.next().and_then(|p| p.parse().ok()).unwrap_or(1);
+        let text = params.next();
+
+        // AltGr isn't a modifier in the protocol, so track it as a key.
+        if end == b'u' && code == KITTY_ALTGR {
+            self.altgr = event != 3;
+        }
+        if self.altgr {
+            mods = mods.add_altgr();
+        }
+
+        let key = match end {
+            b'A' => Key::Up,
+            b'B' => Key::Down,
+            b'C' => Key::Right,
+            b'D' => Key::Left,
+            b'H' => Key::Home,
+            b'F' => Key::End,
+            // Keypad begin (5 with Num Lock off).
+            b'E' => Key::NumFive,
+            b'P' => Key::F1,
+            b'Q' => Key::F2,
+            b'R' => Key::F3,
+            b'S' => Key::F4,
+            b'Z' => {
+                self.key(Mod::new().add_shift(), Key::Tab);
+                return;
+            }
+            b'I' => {
+                self.queue.push_back(Input::FocusGained);
+                return;
+            }
+            b'O' => {
+                self.queue.push_back(Input::FocusLost);
+                return;
+            }
+            b'u' => {
+                let key = kitty(base.unwrap_or(code));
+                // Text that the key typed, falling back to the key code.
+                let text: String = match text {
+                    Some(text) => text
+                        .split(':')
+                        .filter_map(|c| c.parse().ok())
+                        .filter_map(char::from_u32)
+                        .collect(),
+                    None if mods.ctrl() || mods.alt() || mods.super_key() => {
+                        String::new()
+                    }
+                    None => {
+                        let code = if mods.shift() {
+                            shifted.unwrap_or(code)
+                        } else {
+                            code
+                        };
+                        char::from_u32(code)
+                            .filter(|c| !c.is_control() && !is_private(*c))
+                            .into_iter()
+                            .collect()
+                    }
+                };
+                self.kitty_key(mods, key, event, &text);
+                return;
+            }
+            b'~' => match code {
+                1 | 7 => Key::Home,
+                2 => Key::Insert,
+                3 => Key::Del,
+                4 | 8 => Key::End,
+                5 => Key::PageUp,
+                6 => Key::PageDown,
+                11 => Key::F1,
+                12 => Key::F2,
+                13 => Key::F3,
+                14 => Key::F4,
+                15 => Key::F5,
+                17 => Key::F6,
+                18 => Key::F7,
+                19 => Key::F8,
+                20 => Key::F9,
+                21 => Key::F10,
+                23 => Key::F11,
+                24 => Key::F12,
+                200 => {
+                    self.paste = Some(String::new());
+                    return;
+                }
+                _ => return,
+            },
+            _ => return,
+        };
+        self.kitty_key(mods, Some(key), event, "");
+    }
+
+    /// Send a key event, which is a press, repeat or release (1, 2 or 3 from
+    /// the kitty keyboard protocol) if enhancements are enabled.
+    fn kitty_key(
+        &mut self,
+        mods: Mod,
+        key: Option<Key>,
+        event: u8,
+        text: &str,
+    ) {
+        if !self.enhanced {
+            if let Some(key) = key {
+                self.queue
+                    .push_back(Input::Key(mods, key, KeyAction::Press));
+            }
+            self.text(text);
+            if let Some(key) = key {
+                self.queue
+                    .push_back(Input::Key(mods, key, KeyAction::Release));
+            }
+            return;
+        }
+        let action = match event {
+            1 => KeyAction::Press,
+            2 => KeyAction::Repeat,
+            _ => KeyAction::Release,
+        };
+        if let Some(key) = key {
+            self.queue.push_back(Input::Key(mods, key, action));
+        }
+        if action.is_down() {
+            self.text(text);
+        }
+    }
+
+    /// Handle an SGR (1006) mouse report: `button;column;row`.
+    fn sgr_mouse(&mut self, params: &str, pressed: bool) {
+        let mut params = params.split(';').map(|p| p.parse::<u16>().ok());
+        let (button, col, row) =
+            match (params.next(), params.next(), params.next()) {
+                (Some(Some(b)), Some(Some(c)), Some(Some(r))) => (b, c, r),
+                _ => return,
+            };
+        self.mouse(button, col, row, pressed);
+    }
+
+    /// Handle a legacy mouse report (each byte offset by 32).
+    fn legacy_mouse(&mut self, report: &[u8]) {
+        let button = u16::from(report[0].wrapping_sub(32));
+        let col = u16::from(report[1].wrapping_sub(32));
+        let row = u16::from(report[2].wrapping_sub(32));
+        // Legacy reports don't say which button was released.
+        if button & 0b1110_0011 == 3 {
+            return;
+        }
+        self.mouse(button, col, row, true);
+    }
+
+    /// Handle a decoded mouse report.
+    fn mouse(&mut self, button: u16, col: u16, row: u16, pressed: bool) {
+        let mut mods = Mod::new();
+        if button & 4 != 0 {
+            mods = mods.add_shift();
+        }
+        if button & 8 != 0 {
+            mods = mods.add_alt();
+        }
+        if button & 16 != 0 {
+            mods = mods.add_ctrl();
+        }
+
+        if (col, row) != self.pointer {
+            self.pointer = (col, row);
+            // Use the center of the cell.
+            let x = (f32::from(col) - 0.5) / f32::from(self.size.0);
+            let y = (f32::from(row) - 0.5) / f32::from(self.size.1);
+            self.queue.push_back(Input::PointerMove { x, y });
+        }
+
+        // Motion only
+        if button & 32 != 0 {
+            return;
+        }
+
+        if button & 64 != 0 {
+            let (x, y) = match button & 3 {
+                0 => (0, -1),
+                1 => (0, 1),
+                2 => (-1, 0),
+                _ => (1, 0),
+            };
+            let scroll = Scroll::new(
+                ScrollSource::Wheel,
+                x as f32 * SCROLL_DETENT,
+                y as f32 * SCROLL_DETENT,
+            )
+            .with_detents(x, y);
+            self.queue.push_back(Input::Scroll(mods, scroll));
+            return;
+        }
+
+        let btn = match (button & 128 != 0, button & 3) {
+            (false, 0) => Btn::Left,
+            (false, 1) => Btn::Middle,
+            (false, 2) => Btn::Right,
+            (false, _) => return,
+            (true, 0) => Btn::Back,
+            (true, 1) => Btn::Next,
+            (true, _) => Btn::Extra,
+        };
+        self.queue.push_back(Input::Click(mods, btn, pressed));
+    }
+
+    /// Text inside a bracketed paste, up to the end of the paste.
+    fn paste_text(&mut self, bytes: &[u8]) -> Option<usize> {
+        if bytes.starts_with(PASTE_END) {
+            let text = self.paste.take().unwrap_or_default();
+            self.queue.push_back(Input::Paste(text));
+            return Some(PASTE_END.len());
+        }
+        if PASTE_END.starts_with(bytes) {
+            return None;
+        }
+        let (c, used) = utf8(bytes)?;
+        if let (Some(c), Some(paste)) = (c, self.paste.as_mut()) {
+            paste.push(c);
+        }
+        Some(used)
+    }
+
+    /// Clipboard reply inside an OSC 52 sequence, up to the terminating BEL
+    /// or ST (`ESC \`).
+    fn osc_text(&mut self, bytes: &[u8]) -> Option<usize> {
+        let osc = self.osc.as_mut()?;
+        let end = match bytes.iter().position(|&b| b == BEL || b == ESC) {
+            Some(end) => end,
+            None => {
+                osc.extend_from_slice(bytes);
+                return Some(bytes.len());
+            }
+        };
+        osc.extend_from_slice(&bytes[..end]);
+        let used = if bytes[end] == BEL {
+            end + 1
+        } else if end + 1 < bytes.len() {
+            end + 2
+        } else if end > 0 {
+            return Some(end);
+        } else {
+            return None;
+        };
+        let osc = self.osc.take().unwrap_or_default();
+        // `selection;base64`
+        let osc = String::from_utf8_lossy(&osc);
+        let data = osc.rsplit(';').next().unwrap_or("");
+        if let Some(text) = clipboard::decode(data) {
+            self.clipboard = Some(String::from_utf8_lossy(&text).into_owned());
+        }
+        Some(used)
+    }
+
+    /// Send typed text, if there is any.
+    fn text(&mut self, text: &str) {
+        if !text.is_empty() {
+            self.queue.push_back(Input::Text(text.to_string()));
+        }
+    }
+
+    /// Send the characters parsed in a row.
+    fn end_run(&mut self) {
+        let run = std::mem::take(&mut self.run);
+        let mut chars = run.chars();
+        match (chars.next(), chars.next()) {
+            (None, _) => {}
+            (Some(c), None) => match printable(c) {
+                Some((key, mods)) => {
+                    self.queue.push_back(Input::Key(
+                        mods,
+                        key,
+                        KeyAction::Press,
+                    ));
+                    self.queue.push_back(Input::Text(run));
+                    self.queue.push_back(Input::Key(
+                        mods,
+                        key,
+                        KeyAction::Release,
+                    ));
+                }
+                None => self.queue.push_back(Input::Text(run)),
+            },
+            _ => {
+                for (key, mods) in run.chars().filter_map(printable) {
+                    self.key(mods, key);
+                }
+                self.queue.push_back(Input::Text(run));
+            }
+        }
+    }
+
+    /// Send a key press and release.
+    fn key(&mut self, mods: Mod, key: Key) {
+        self.queue
+            .push_back(Input::Key(mods, key, KeyAction::Press));
+        self.queue
+            .push_back(Input::Key(mods, key, KeyAction::Release));
+    }
+}
+
+/// Decode a UTF-8 character, returning it (`None` if invalid) and its length
+/// (`None` if incomplete).
+fn utf8(bytes: &[u8]) -> Option<(Option<char>, usize)> {
+    let len = match bytes[0] {
+        0x00..=0x7F => 1,
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => return Some((None, 1)),
+    };
+    let bytes = bytes.get(..len)?;
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Some((text.chars().next(), len)),
+        Err(_) => Some((None, 1)),
+    }
+}
+
+/// Check if a character is in the private use area (kitty uses these for keys
+/// that don't have a character).
+fn is_private(c: char) -> bool {
+    ('\u{E000}'..='\u{F8FF}').contains(&c)
+}
+
+/// Converter for kitty keyboard protocol key codes to human keycodes.
+fn kitty(code: u32) -> Option<Key> {
+    let key = match code {
+        9 => Key::Tab,
+        13 => Key::Enter,
+        27 => Key::Esc,
+        127 => Key::Delete,
+        57358 => Key::Caps,
+        57359 => Key::Scroll,
+        57360 => Key::Num,
+        57361 => Key::Screenshot,
+        57362 => Key::Pause,
+        57363 => Key::Menu,
+        57399 => Key::NumZero,
+        57400 => Key::NumOne,
+        57401 => Key::NumTwo,
+        57402 => Key::NumThree,
+        57403 => Key::NumFour,
+        57404 => Key::NumFive,
+        57405 => Key::NumSix,
+        57406 => Key::NumSeven,
+        57407 => Key::NumEight,
+        57408 => Key::NumNine,
+        57409 => Key::NumPeriod,
+        57410 => Key::NumSlash,
+        57411 => Key::NumStar,
+        57412 => Key::NumMinus,
+        57413 => Key::NumPlus,
+        57414 => Key::NumEnter,
+        57415 => Key::NumEqual,
+        57416 => Key::NumComma,
+        // Numpad keys with Num Lock off
+        57417 => Key::NumFour,
+        57418 => Key::NumSix,
+        57419 => Key::NumEight,
+        57420 => Key::NumTwo,
+        57421 => Key::NumNine,
+        57422 => Key::NumThree,
+        57423 => Key::NumSeven,
+        57424 => Key::NumOne,
+        57425 => Key::NumZero,
+        57426 => Key::NumPeriod,
+        57427 => Key::NumFive,
+        57428..=57430 => Key::Play,
+        57432 => Key::Stop,
+        57435 => Key::Next,
+        57436 => Key::Prev,
+        57438 => Key::Quieter,
+        57439 => Key::Louder,
+        57440 => Key::Mute,
+        57441 => Key::LShift,
+        57442 => Key::LCtrl,
+        57443 => Key::LAlt,
+        57447 => Key::RShift,
+        57448 => Key::RCtrl,
+        57449 => Key::RAlt,
+        KITTY_ALTGR => Key::Thumb,
+        _ => return printable(char::from_u32(code)?).map(|(key, _)| key),
+    };
+    Some(key)
+}
+
+/// Decode an xterm modifier parameter (1 + bitmask, extended by the kitty
+/// keyboard protocol with super, hyper, meta and lock keys).
+fn modifiers(param: Option<&str>) -> Mod {
+    let bits = param
+        .and_then(|p| p.split(':').next())
+        .and_then(|p| p.parse::<u16>().ok())
+        .unwrap_or(1)
+        .saturating_sub(1);
+    let mut mods = Mod::new();
+    if bits & 1 != 0 {
+        mods = mods.add_shift();
+    }
+    if bits & 2 != 0 {
+        mods = mods.add_alt();
+    }
+    if bits & 4 != 0 {
+        mods = mods.add_ctrl();
+    }
+    // Super (8) or Meta (32)
+    if bits & (8 | 32) != 0 {
+        mods = mods.add_super();
+    }
+    if bits & 64 != 0 {
+        mods = mods.add_caps_lock();
+    }
+    if bits & 128 != 0 {
+        mods = mods.add_num_lock();
+    }
+    mods
+}
+
+/// Converter for control characters to human keycodes.
+fn control(byte: u8) -> Option<(Key, Mod)> {
+    let ctrl = Mod::new().add_ctrl();
+    let key = match byte {
+        0x00 => (Key::Space, ctrl),
+        0x08 => (Key::Delete, ctrl),
+        0x09 => (Key::Tab, Mod::new()),
+        0x0D => (Key::Enter, Mod::new()),
+        0x01..=0x1A => (printable(char::from(byte + 0x60))?.0, ctrl),
+        0x1B => (Key::Esc, Mod::new()),
+        0x1C => (Key::Backslash, ctrl),
+        0x1D => (Key::BracketClose, ctrl),
+        0x1E => (Key::Six, ctrl.add_shift()),
+        0x1F => (Key::Slash, ctrl),
+        0x7F => (Key::Delete, Mod::new()),
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Converter for printable characters to human keycodes (assuming a US
+/// keyboard layout).
+fn printable(c: char) -> Option<(Key, Mod)> {
+    let shift = Mod::new().add_shift();
+    let key = match c {
+        'a' | 'A' => Key::A,
+        'b' | 'B' => Key::B,
+        'c' | 'C' => Key::C,
+        'd' | 'D' => Key::D,
+        'e' | 'E' => Key::E,
+        'f' | 'F' => Key::F,
+        'g' | 'G' => Key::G,
+        'h' | 'H' => Key::H,
+        'i' | 'I' => Key::I,
+        'j' | 'J' => Key::J,
+        'k' | 'K' => Key::K,
+        'l' | 'L' => Key::L,
+        'm' | 'M' => Key::M,
+        'n' | 'N' => Key::N,
+        'o' | 'O' => Key::O,
+        'p' | 'P' => Key::P,
+        'q' | 'Q' => Key::Q,
+        'r' | 'R' => Key::R,
+        's' | 'S' => Key::S,
+        't' | 'T' => Key::T,
+        'u' | 'U' => Key::U,
+        'v' | 'V' => Key::V,
+        'w' | 'W' => Key::W,
+        'x' | 'X' => Key::X,
+        'y' | 'Y' => Key::Y,
+        'z' | 'Z' => Key::Z,
+        '1' | '!' => Key::One,
+        '2' | '@' => Key::Two,
+        '3' | '#' => Key::Three,
+        '4' | '$' => Key::Four,
+        '5' | '%' => Key::Five,
+        '6' | '^' => Key::Six,
+        '7' | '&' => Key::Seven,
+        '8' | '*' => Key::Eight,
+        '9' | '(' => Key::Nine,
+        '0' | ')' => Key::Zero,
+        '-' | '_' => Key::Minus,
+        '=' | '+' => Key::Equal,
+        '[' | '{' => Key::BracketOpen,
+        ']' | '}' => Key::BracketClose,
+        '\\' | '|' => Key::Backslash,
+        ';' | ':' => Key::Semicolon,
+        '\'' | '"' => Key::Apostrophe,
+        '`' | '~' => Key::Backtick,
+        ',' | '<' => Key::Comma,
+        '.' | '>' => Key::Period,
+        '/' | '?' => Key::Slash,
+        ' ' => Key::Space,
+        _ => return None,
+    };
+    let shifted =
+        c.is_ascii_uppercase() || "!@#$%^&*()_+{}|:\"~<>?".contains(c);
+    Some((key, if shifted { shift } else { Mod::new() }))
+}
@@ -0,0 +1,265 @@
+// Human
+// Copyright © 2020-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Keyboard and mouse input from the terminal connected to stdin (Linux).
+
+use std::{
+    io::{stdin, stdout, Error, ErrorKind, Read, Result, Write},
+    os::raw::{c_int, c_ulong},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use smelling_salts::{Device, Watcher};
+
+use crate::{
+    clipboard,
+    clock::{self, Timer},
+    queue::Queue,
+    Config, Device as Info, DeviceId, DeviceKind, Event, Input, TermParser,
+};
+
+const STDIN: c_int = 0;
+const STDOUT: c_int = 1;
+
+const F_GETFL: c_int = 3;
+const F_SETFL: c_int = 4;
+const O_NONBLOCK: c_int = 0o0004000;
+const TCSANOW: c_int = 0;
+const TIOCGWINSZ: c_ulong = 0x5413;
+
+/// How long to wait for the rest of an escape sequence before a lone Escape
+/// byte is taken to be the Escape key.
+const ESC_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// The terminal is the only device, since it doesn't say which keyboard or
+/// mouse input came from.
+const TERMINAL: DeviceId = DeviceId(0);
+
+/// Enable mouse reporting (all motion, SGR encoding), focus reporting and
+/// bracketed paste.
+const ENABLE: &[u8] = b"\x1b[?1003h\x1b[?1006h\x1b[?1004h\x1b[?2004h";
+/// Undo `ENABLE`.
+const DISABLE: &[u8] = b"\x1b[?2004l\x1b[?1004l\x1b[?1006l\x1b[?1003l";
+/// Query kitty keyboard protocol support, followed by a primary device
+/// attributes request which every terminal replies to.
+const KITTY_QUERY: &[u8] = b"\x1b[?u\x1b[c";
+/// Push kitty keyboard protocol flags: disambiguate escape codes (1), report
+/// event types (2), report alternate keys (4), report all keys as escape codes
+/// (8) and report associated text (16).
+const KITTY_ENABLE: &[u8] = b"\x1b[>31u";
+/// Pop the flags pushed by `KITTY_ENABLE`.
+const KITTY_DISABLE: &[u8] = b"\x1b[<u";
+
+/// `struct termios`, treated as opaque since it's only passed between libc
+/// functions (large enough for any libc's definition).
+#[repr(C, align(8))]
+#[derive(Copy, Clone)]
+struct Termios([u8; 256]);
+
+/// `struct winsize`
+#[repr(C)]
+#[derive(Default)]
+struct Winsize {
+    rows: u16,
+    cols: u16,
+    xpixel: u16,
+    ypixel: u16,
+}
+
+extern "C" {
+    fn tcgetattr(fd: c_int, termios: *mut Termios) -> c_int;
+    fn tcsetattr(fd: c_int, action: c_int, termios: *const Termios) -> c_int;
+    fn cfmakeraw(termios: *mut Termios);
+    fn fcntl(fd: c_int, cmd: c_int, ...) -> c_int;
+    fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+}
+
+/// The terminal, in raw mode until dropped.
+pub(crate) struct Tty {
+    // Async handle for stdin.
+    device: Device,
+    // Terminal settings to restore.
+    saved: Termios,
+    // File status flags of stdin to restore.
+    flags: c_int,
+    // Parser for bytes read from stdin.
+    parser: TermParser,
+    // Parsed events waiting to be returned.
+    queue: Queue,
+    // True once stdin has been closed.
+    eof: bool,
+    // True once kitty keyboard protocol enhancements have been enabled.
+    enhanced: bool,
+    // Device attributes replies still expected from `KITTY_QUERY` (the rest
+    // are from clipboard reads).
+    queried: usize,
+    // Timer for sending a lone Escape byte as the Escape key.
+    timer: Option<Timer>,
+    // When to send a lone Escape byte as the Escape key, if there is one.
+    escape: Option<Duration>,
+}
+
+impl Tty {
+    /// Put the terminal into raw mode, failing if stdin isn't a terminal.
+    #[allow(unsafe_code)]
+    pub(crate) fn new(config: Config) -> Result<Self> {
+        let mut saved = Termios([0; 256]);
+        if unsafe { tcgetattr(STDIN, &mut saved) } == -1 {
+            return Err(Error::last_os_error());
+        }
+        let mut raw = saved;
+        unsafe { cfmakeraw(&mut raw) };
+        if unsafe { tcsetattr(STDIN, TCSANOW, &raw) } == -1 {
+            return Err(Error::last_os_error());
+        }
+        let flags = unsafe { fcntl(STDIN, F_GETFL) };
+        let _ = unsafe { fcntl(STDIN, F_SETFL, flags | O_NONBLOCK) };
+
+        let mut stdout = stdout();
+        let _ = stdout.write_all(ENABLE);
+        let _ = stdout.write_all(KITTY_QUERY);
+        let _ = stdout.flush();
+
+        let mut queue = Queue::new();
+        queue.configure(config);
+        let info =
+            Info::new(TERMINAL, "Terminal".to_string(), DeviceKind::Keyboard);
+        let input = Input::DeviceAdded(info);
+        queue.push(Event::new(clock::now(), input).with_device(TERMINAL));
+        let mut tty = Tty {
+            device: Device::new(STDIN, Watcher::new().input()),
+            saved,
+            flags,
+            parser: TermParser::new(),
+            queue,
+            eof: false,
+            enhanced: false,
+            queried: 1,
+            timer: Timer::new(),
+            escape: None,
+        };
+        tty.resize();
+        clipboard::set_terminal(true);
+        Ok(tty)
+    }
+
+    pub(crate) fn poll(&mut self, cx: &mut Context<'_>) -> Poll<Event> {
+        if let Some(event) = self.queue.pop() {
+            return Poll::Ready(event);
+        }
+
+        let mut buf = [0; 4096];
+        let mut stdin = stdin();
+        let mut read = false;
+        while !self.eof {
+            match stdin.read(&mut buf) {
+                Ok(0) => self.eof = true,
+                Ok(len) => {
+                    self.resize();
+                    self.parser.push(&buf[..len]);
+                    read = true;
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+
+        // An escape sequence may be split across reads, so a lone Escape byte
+        // is only the Escape key once nothing else has arrived for a while.
+        if !self.parser.pending() {
+            self.escape = None;
+        } else if self.eof || self.timer.is_none() {
+            self.parser.flush();
+        } else {
+            let now = clock::now();
+            if read || self.escape.is_none() {
+                self.escape = Some(now + ESC_TIMEOUT);
+            }
+            match (self.escape, self.timer.as_mut()) {
+                (Some(deadline), _) if deadline <= now => {
+                    self.parser.flush();
+                    self.escape = None;
+                }
+                (Some(deadline), Some(timer)) => timer.wake_at(deadline, cx),
+                _ => {}
+            }
+        }
+
+        // Switch to the kitty keyboard protocol once it's known to work.
+        if !self.enhanced && self.parser.kitty() == Some(true) {
+            let mut stdout = stdout();
+            let _ = stdout.write_all(KITTY_ENABLE);
+            let _ = stdout.flush();
+            self.parser.set_enhanced(true);
+            self.enhanced = true;
+        }
+
+        // Replies to clipboard reads.
+        if let Some(text) = self.parser.clipboard() {
+            clipboard::terminal_reply(Some(text));
+        }
+        for _ in 0..self.parser.attributes() {
+            if self.queried > 0 {
+                self.queried -= 1;
+            } else {
+                clipboard::terminal_reply(None);
+            }
+        }
+
+        // Terminals don't report when keys were pressed, so use the time the
+        // input arrived.
+        let time = clock::now();
+        while let Some(input) = self.parser.pop() {
+            self.queue
+                .push(Event::new(time, input).with_device(TERMINAL));
+        }
+        if let Some(event) = self.queue.pop() {
+            return Poll::Ready(event);
+        }
+
+        if !self.eof {
+            self.device.register_waker(cx.waker());
+        }
+        Poll::Pending
+    }
+
+    /// Update the parser with the current size of the terminal.
+    #[allow(unsafe_code)]
+    fn resize(&mut self) {
+        let mut size = Winsize::default();
+        if unsafe { ioctl(STDOUT, TIOCGWINSZ, &mut size) } != -1 {
+            self.parser.resize(size.cols, size.rows);
+            // Terminals that don't know their size in pixels report zero.
+            let (width, height) = if size.xpixel != 0 && size.ypixel != 0 {
+                (size.xpixel, size.ypixel)
+            } else {
+                (size.cols, size.rows)
+            };
+            self.queue
+                .set_surface(f32::from(width), f32::from(height), 1.0);
+        }
+    }
+}
+
+impl Drop for Tty {
+    #[allow(unsafe_code)]
+    fn drop(&mut self) {
+        clipboard::set_terminal(false);
+        let mut stdout = stdout();
+        if self.enhanced {
+            let _ = stdout.write_all(KITTY_DISABLE);
+        }
+        let _ = stdout.write_all(DISABLE);
+        let _ = stdout.flush();
+        let _ = unsafe { fcntl(STDIN, F_SETFL, self.flags) };
+        let _ = unsafe { tcsetattr(STDIN, TCSANOW, &self.saved) };
+    }
+}
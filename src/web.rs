@@ -12,75 +12,311 @@
 //! talking to a window manager, input can be received here.
 
 use std::{
-    sync::atomic::{AtomicU64, Ordering::SeqCst},
     task::{Context, Poll, Waker},
+    time::Duration,
 };
 
-use wasm_bindgen::{closure::Closure, JsCast};
+use wasm_bindgen::{closure::Closure, prelude::wasm_bindgen, JsCast};
 use web_sys::{
-    AddEventListenerOptions, Event, EventTarget, HtmlElement, HtmlInputElement,
-    InputEvent, KeyboardEvent, MouseEvent, WheelEvent,
+    AddEventListenerOptions, ClipboardEvent, CompositionEvent, Event,
+    EventTarget, HtmlElement, HtmlInputElement, InputEvent, KeyboardEvent,
+    MouseEvent, Node, PointerEvent, TouchEvent, WheelEvent,
 };
 
-use crate::{Input, Key, Mod, Btn};
+use crate::{
+    queue::Queue, Config, Device, DeviceId, DeviceKind, Input, Key, KeyAction,
+    KeyboardState, Mod, Btn, PenTool, Scroll, ScrollSource,
+};
+
+#[wasm_bindgen]
+extern "C" {
+    /// A `WheelEvent`, with the non-standard `wheelDeltaX` and `wheelDeltaY`
+    /// (in 120ths of a detent, counting up for scrolling up).
+    type LegacyWheelEvent;
+
+    #[wasm_bindgen(method, getter, js_name = wheelDeltaX)]
+    fn wheel_delta_x(this: &LegacyWheelEvent) -> Option<f64>;
+
+    #[wasm_bindgen(method, getter, js_name = wheelDeltaY)]
+    fn wheel_delta_y(this: &LegacyWheelEvent) -> Option<f64>;
+}
 
 struct WebInput {
-    input: Option<Input>,
+    queue: Queue,
     waker: Option<Waker>,
+    // Keys currently held down, to filter out repeats.
+    keyboard: KeyboardState,
+    // Whether the pen has been announced yet.
+    pen: bool,
+    // Whether the pointer is locked.
+    pointer_lock: bool,
+    // Smooth scrolling (in pixels) that hasn't added up to a detent yet.
+    scroll: (f32, f32),
+    // Mouse buttons pressed (in the element, if input is scoped to one).
+    buttons: u8,
+    // Element that input is scoped to, if any.
+    element: Option<HtmlElement>,
 }
 
 static mut WEB_INPUT: WebInput = WebInput {
-    input: None,
+    queue: Queue::new(),
     waker: None,
+    keyboard: KeyboardState::new(),
+    pen: false,
+    pointer_lock: false,
+    scroll: (0.0, 0.0),
+    buttons: 0,
+    element: None,
 };
 
-static KEYBOARD_STATE: AtomicU64 = AtomicU64::new(0);
-static KEYBOARD_STATE_FN: AtomicU64 = AtomicU64::new(0);
+/// Browsers don't say which keyboard or mouse an event came from, so all
+/// keyboards are reported as one device, all mice as another, all
+/// touchscreens as a third, and all pens as a fourth (announced when first
+/// used, since browsers can't tell if there is one).
+const KEYBOARD: DeviceId = DeviceId(0);
+const MOUSE: DeviceId = DeviceId(1);
+const TOUCHSCREEN: DeviceId = DeviceId(2);
+const PEN: DeviceId = DeviceId(3);
+
+/// Pixels in one detent of a scroll wheel (three lines).
+const SCROLL_DETENT: f32 = 3.0 * 16.0;
 
-fn key_up_state(key: Key) -> bool {
-    if key as i8 >= 64 {
-        let bitflag = 1 << (key as i8 - 64);
-        let new = KEYBOARD_STATE_FN.fetch_and(!bitflag, SeqCst);
-        let old = KEYBOARD_STATE_FN.load(SeqCst);
+#[allow(unsafe_code)]
+fn state<'a>() -> &'a mut WebInput {
+    unsafe { &mut WEB_INPUT }
+}
 
-        old != new
+pub(crate) fn poll(cx: &mut Context<'_>) -> Poll<crate::Event> {
+    let state = state();
+    if let Some(event) = state.queue.pop() {
+        Poll::Ready(event)
     } else {
-        let bitflag = 1 << (key as i8);
-        let new = KEYBOARD_STATE.fetch_and(!bitflag, SeqCst);
-        let old = KEYBOARD_STATE.load(SeqCst);
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Queue a key press or release from a DOM event.  Presses of keys that are
+/// already held down are repeats, and releases of keys that aren't are ignored.
+fn send_key(event: &KeyboardEvent, mods: Mod, key: Key, pressed: bool) {
+    let keyboard = &mut state().keyboard;
+    let action = match (pressed, keyboard.is_pressed(key)) {
+        (true, false) => KeyAction::Press,
+        (true, true) => KeyAction::Repeat,
+        (false, true) => KeyAction::Release,
+        (false, false) => return,
+    };
+    let input = Input::Key(mods, key, action);
+    keyboard.update(&input);
+    keyboard.next_frame();
+    send(event, KEYBOARD, input);
+}
 
-        old != new
+/// Get the area that positions are relative to (the element that input is
+/// scoped to, or the window), in CSS pixels: left, top, width and height.
+fn area() -> (f32, f32, f32, f32) {
+    if let Some(ref element) = state().element {
+        let rect = element.get_bounding_client_rect();
+        return (
+            rect.left() as f32,
+            rect.top() as f32,
+            rect.width() as f32,
+            rect.height() as f32,
+        );
     }
+    let window = web_sys::window().unwrap();
+    let width = window.inner_width().unwrap().as_f64().unwrap() as f32;
+    let height = window.inner_height().unwrap().as_f64().unwrap() as f32;
+    (0.0, 0.0, width, height)
 }
 
-fn key_down_state(key: Key) -> bool {
-    if key as i8 >= 64 {
-        let bitflag = 1 << (key as i8 - 64);
-        let new = KEYBOARD_STATE_FN.fetch_or(bitflag, SeqCst);
-        let old = KEYBOARD_STATE_FN.load(SeqCst);
+/// Convert a DOM client position into normalized coordinates in the area.
+///
+/// This also updates the queue's surface size, which is only needed for
+/// positions, so it isn't measured for every event.
+fn position(client_x: i32, client_y: i32) -> (f32, f32) {
+    let (left, top, width, height) = area();
+    let scale = web_sys::window().unwrap().device_pixel_ratio() as f32;
+    state().queue.set_surface(width, height, scale);
+    (
+        (client_x as f32 - left) / width,
+        (client_y as f32 - top) / height,
+    )
+}
 
-        old != new
-    } else {
-        let bitflag = 1 << (key as i8);
-        let new = KEYBOARD_STATE.fetch_or(bitflag, SeqCst);
-        let old = KEYBOARD_STATE.load(SeqCst);
+/// Check if a DOM `mouseover` or `mouseout` event crossed the edge of the
+/// element that input is scoped to (or the window), rather than moving
+/// between elements inside it.
+fn crossed(event: &MouseEvent) -> bool {
+    match state().element {
+        Some(ref element) => {
+            contains(element, event.target())
+                && !contains(element, event.related_target())
+        }
+        None => event.related_target().is_none(),
+    }
+}
+
+/// Check if `target` is `element` or inside it.
+fn contains(element: &HtmlElement, target: Option<EventTarget>) -> bool {
+    let target = target.and_then(|t| t.dyn_into::<Node>().ok());
+    target.is_some() && element.contains(target.as_ref())
+}
 
-        old != new
+/// Focus the hidden text input once the current event has been handled,
+/// unless something else on the page (like a form field) has taken focus.
+fn capture_focus() {
+    let focus = Closure::once_into_js(|| {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let free = document.active_element().is_none_or(|active| {
+            let active: &Node = active.as_ref();
+            let body = document.body();
+            body.is_some_and(|body| body.is_same_node(Some(active)))
+                || state()
+                    .element
+                    .as_ref()
+                    .is_some_and(|element| element.contains(Some(active)))
+        });
+        if let Some(text_input) = text_input().filter(|_| free) {
+            let _ = text_input.focus();
+        }
+    });
+    let _ = web_sys::window()
+        .unwrap()
+        .set_timeout_with_callback(focus.unchecked_ref());
+}
+
+/// Queue an input event from a DOM event.
+fn send(event: &Event, device: DeviceId, input: Input) {
+    let time = Duration::from_secs_f64(event.time_stamp() / 1000.0);
+    push(crate::Event::new(time, input).with_device(device));
+}
+
+/// Queue an input event about the window (not from any device) from a DOM
+/// event.
+fn send_window(event: &Event, input: Input) {
+    let time = Duration::from_secs_f64(event.time_stamp() / 1000.0);
+    push(crate::Event::new(time, input));
+}
+
+/// Queue an input event, waking the listener if it's waiting.
+fn push(event: crate::Event) {
+    let state = state();
+    state.queue.push(event);
+    if let Some(waker) = state.waker.take() {
+        waker.wake();
     }
 }
 
-#[allow(unsafe_code)]
-fn state<'a>() -> &'a mut WebInput {
-    unsafe { &mut WEB_INPUT }
+/// Constructor for the input sent for a changed touch.
+type TouchInput = fn(crate::Touch) -> Input;
+
+/// Queue an input event for each touch that changed in a DOM touch event.
+fn send_touches(event: &TouchEvent, input: TouchInput) {
+    let touches = event.changed_touches();
+    for touch in (0..touches.length()).filter_map(|i| touches.item(i)) {
+        // Force is 0.0 if it isn't supported.
+        let pressure = match touch.force() {
+            force if force > 0.0 => force,
+            _ => 0.5,
+        };
+        let (x, y) = position(touch.client_x(), touch.client_y());
+        let id = touch.identifier() as u32;
+        let touch = crate::Touch::new(id, x, y, pressure);
+        send(event, TOUCHSCREEN, input(touch));
+    }
 }
 
-pub(crate) fn poll(cx: &mut Context<'_>) -> Poll<Input> {
+/// Queue a pen event from a DOM pointer event, if it came from a pen.
+fn send_pen(event: &PointerEvent) {
+    if event.pointer_type() != "pen" {
+        return;
+    }
+    if !state().pen {
+        state().pen = true;
+        let device = Device::new(PEN, "Pen".to_string(), DeviceKind::Pen);
+        send(event, PEN, Input::DeviceAdded(device));
+    }
+    // Buttons: 1 for the tip touching, 2 for the barrel button and 32 for
+    // the eraser touching.
+    let buttons = event.buttons();
+    let tool = if buttons & 32 != 0 || event.button() == 5 {
+        PenTool::Eraser
+    } else {
+        PenTool::Pen
+    };
+    let contact = buttons & (1 | 32) != 0;
+    let pressure = if contact { event.pressure() } else { 0.0 };
+    let (x, y) = position(event.client_x(), event.client_y());
+    let pen = crate::Pen::new(tool, x, y)
+        .with_contact(contact, pressure)
+        .with_tilt(event.tilt_x() as f32, event.tilt_y() as f32)
+        .with_twist(event.twist() as f32)
+        .with_barrel(buttons & 2 != 0);
+    send(event, PEN, Input::Pen(pen));
+}
+
+/// Queue a scroll from a DOM wheel event.
+fn send_scroll(event: &WheelEvent) {
+    let mods = ptr_modifier(event);
+    let mode = event.delta_mode();
+    let (x, y) = (event.delta_x() as f32, event.delta_y() as f32);
+    if x == 0.0 && y == 0.0 {
+        return;
+    }
+    let legacy: &LegacyWheelEvent = event.unchecked_ref();
+    let wheel = (
+        legacy.wheel_delta_x().unwrap_or(0.0) as f32,
+        legacy.wheel_delta_y().unwrap_or(0.0) as f32,
+    );
+
+    // Browsers don't say what the source is, but for touchpads the legacy
+    // delta is three times the pixel delta, and for wheels it's whole
+    // detents.
+    let touchpad = mode == 0x00
+        && wheel != (0.0, 0.0)
+        && (wheel.0 + 3.0 * x).abs() < 1.0
+        && (wheel.1 + 3.0 * y).abs() < 1.0;
+    let notched = wheel != (0.0, 0.0)
+        && wheel.0 % 120.0 == 0.0
+        && wheel.1 % 120.0 == 0.0;
+    let source = if touchpad {
+        ScrollSource::Touchpad
+    } else if notched || mode != 0x00 {
+        ScrollSource::Wheel
+    } else {
+        ScrollSource::Continuous
+    };
+
+    let (x, y) = (delta(mode, x), delta(mode, y));
     let state = state();
-    if let Some(input) = state.input.take() {
-        Poll::Ready(input)
+    let detents = if source == ScrollSource::Wheel && notched {
+        state.scroll = (0.0, 0.0);
+        (-wheel.0 as i32 / 120, -wheel.1 as i32 / 120)
     } else {
-        state.waker = Some(cx.waker().clone());
-        Poll::Pending
+        state.scroll.0 += x;
+        state.scroll.1 += y;
+        let detents = (
+            (state.scroll.0 / SCROLL_DETENT).trunc(),
+            (state.scroll.1 / SCROLL_DETENT).trunc(),
+        );
+        state.scroll.0 -= detents.0 * SCROLL_DETENT;
+        state.scroll.1 -= detents.1 * SCROLL_DETENT;
+        (detents.0 as i32, detents.1 as i32)
+    };
+    let scroll = Scroll::new(source, x, y).with_detents(detents.0, detents.1);
+    send(event, MOUSE, Input::Scroll(mods, scroll));
+}
+
+/// Check if a key press is a clipboard shortcut (Ctrl/Cmd + C, V or X, or
+/// Shift + Insert), which the browser must handle to send `copy`, `cut` and
+/// `paste` events.
+fn is_clipboard(event: &KeyboardEvent) -> bool {
+    let shortcut = event.ctrl_key() || event.meta_key();
+    match event.key().to_lowercase().as_str() {
+        "c" | "v" | "x" => shortcut && !event.alt_key(),
+        "insert" => event.shift_key() && !shortcut && !event.alt_key(),
+        _ => false,
     }
 }
 
@@ -105,146 +341,114 @@ fn is_printing(keycode: &str) -> bool {
         )
 }
 
-fn ptr_modifier(event: &MouseEvent) -> Mod {
+/// Modifier state from `getModifierState()`.
+fn modifiers(get: impl Fn(&str) -> bool) -> Mod {
     let mut mods = Mod::new();
 
-    let ctrl =
-        event.get_modifier_state("Control") || event.get_modifier_state("Meta");
-    let alt = event.get_modifier_state("Alt");
-    let shift = event.get_modifier_state("Shift");
-
-    if ctrl {
+    if get("Control") {
         mods = mods.add_ctrl();
     }
 
-    if alt {
+    if get("Meta") {
+        mods = mods.add_super();
+    }
+
+    if get("Alt") {
         mods = mods.add_alt();
     }
 
-    if shift {
+    if get("AltGraph") {
+        mods = mods.add_altgr();
+    }
+
+    if get("Shift") {
         mods = mods.add_shift();
     }
 
+    if get("CapsLock") {
+        mods = mods.add_caps_lock();
+    }
+
+    if get("NumLock") {
+        mods = mods.add_num_lock();
+    }
+
+    if get("ScrollLock") {
+        mods = mods.add_scroll_lock();
+    }
+
     mods
 }
 
-fn key_modifier(event: &KeyboardEvent, mods: &mut Mod) -> bool {
-    let ctrl =
-        event.get_modifier_state("Control") || event.get_modifier_state("Meta");
-    let alt = event.get_modifier_state("Alt");
-    let shift = event.get_modifier_state("Shift");
+fn ptr_modifier(event: &MouseEvent) -> Mod {
+    modifiers(|key| event.get_modifier_state(key))
+}
 
-    if ctrl {
-        *mods = mods.add_ctrl();
-    }
+fn key_modifier(event: &KeyboardEvent) -> Mod {
+    modifiers(|key| event.get_modifier_state(key))
+}
 
-    if alt {
-        *mods = mods.add_alt();
-    }
+/// Get the hidden text input element that receives text and IME input.
+fn text_input() -> Option<HtmlInputElement> {
+    web_sys::window()?
+        .document()?
+        .get_element_by_id("rust_crate_human__")?
+        .dyn_into()
+        .ok()
+}
 
-    if shift {
-        *mods = mods.add_shift();
+/// Convert a UTF-16 offset (as used by the DOM) into a byte offset in `text`.
+fn byte_offset(text: &str, utf16: u32) -> usize {
+    let mut units = 0;
+    for (i, c) in text.char_indices() {
+        if units >= utf16 {
+            return i;
+        }
+        units += c.len_utf16() as u32;
     }
+    text.len()
+}
 
-    ctrl || alt
+/// Move the hidden text input, which is where browsers put the IME candidate
+/// window.
+pub(crate) fn set_cursor_area(x: f32, y: f32, width: f32, height: f32) {
+    let (left, top, _, _) = area();
+    let (x, y) = (left + x, top + y);
+    if let Some(text_input) = text_input() {
+        let _ = text_input.set_attribute(
+            "style",
+            &format!(
+                "\
+                    border:0;\
+                    padding:0;\
+                    margin:0;\
+                    position:fixed;\
+                    top:{}px;\
+                    left:{}px;\
+                    width:{}px;\
+                    height:{}px;\
+                    opacity:0;\
+                ",
+                y, x, width, height,
+            ),
+        );
+    }
 }
 
-/// Coverter for js keycode to human keycodes.
-fn keycode(keycode: &str) -> Option<(Key, Mod)> {
-    let key = match keycode {
-        "Digit0" | "Numpad0" => (Key::Zero, Mod::new()),
-        "Digit1" | "Numpad1" => (Key::One, Mod::new()),
-        "Digit2" | "Numpad2" => (Key::Two, Mod::new()),
-        "Digit3" | "Numpad3" => (Key::Three, Mod::new()),
-        "Digit4" | "Numpad4" => (Key::Four, Mod::new()),
-        "Digit5" | "Numpad5" => (Key::Five, Mod::new()),
-        "Digit6" | "Numpad6" => (Key::Six, Mod::new()),
-        "Digit7" | "Numpad7" => (Key::Seven, Mod::new()),
-        "Digit8" | "Numpad8" => (Key::Eight, Mod::new()),
-        "Digit9" | "Numpad9" => (Key::Nine, Mod::new()),
-        "Enter" | "NumpadEnter" => (Key::Enter, Mod::new()),
-        "KeyA" => (Key::A, Mod::new()),
-        "KeyB" => (Key::B, Mod::new()),
-        "KeyC" => (Key::C, Mod::new()),
-        "KeyD" => (Key::D, Mod::new()),
-        "KeyE" => (Key::E, Mod::new()),
-        "KeyF" => (Key::F, Mod::new()),
-        "KeyG" => (Key::G, Mod::new()),
-        "KeyH" => (Key::H, Mod::new()),
-        "KeyI" => (Key::I, Mod::new()),
-        "KeyJ" => (Key::J, Mod::new()),
-        "KeyK" => (Key::K, Mod::new()),
-        "KeyL" => (Key::L, Mod::new()),
-        "KeyM" => (Key::M, Mod::new()),
-        "KeyN" => (Key::N, Mod::new()),
-        "KeyO" => (Key::O, Mod::new()),
-        "KeyP" => (Key::P, Mod::new()),
-        "KeyQ" => (Key::Q, Mod::new()),
-        "KeyR" => (Key::R, Mod::new()),
-        "KeyS" => (Key::S, Mod::new()),
-        "KeyT" => (Key::T, Mod::new()),
-        "KeyU" => (Key::U, Mod::new()),
-        "KeyV" => (Key::V, Mod::new()),
-        "KeyW" => (Key::W, Mod::new()),
-        "KeyX" => (Key::X, Mod::new()),
-        "KeyY" => (Key::Y, Mod::new()),
-        "KeyZ" => (Key::Z, Mod::new()),
-        "ArrowUp" => (Key::Up, Mod::new()),
-        "ArrowDown" => (Key::Down, Mod::new()),
-        "ArrowLeft" => (Key::Left, Mod::new()),
-        "ArrowRight" => (Key::Right, Mod::new()),
-        "Space" => (Key::Space, Mod::new()),
-        "Tab" => (Key::Tab, Mod::new()),
-        "Backspace" => (Key::Backspace, Mod::new()),
-        "Delete" => (Key::Delete, Mod::new()),
-        "Escape" => (Key::Back, Mod::new()),
-        "Minus" => (Key::Minus, Mod::new()),
-        "Equal" => (Key::Equal, Mod::new()),
-        "Insert" => (Key::Insert, Mod::new()),
-        "PageUp" => (Key::PageUp, Mod::new()),
-        "PageDown" => (Key::PageDown, Mod::new()),
-        "Home" => (Key::Home, Mod::new()),
-        "End" => (Key::End, Mod::new()),
-        "Backslash" => (Key::Backslash, Mod::new()),
-        "BracketLeft" => (Key::BracketOpen, Mod::new()),
-        "BracketRight" => (Key::BracketClose, Mod::new()),
-        "Quote" => (Key::Apostrophe, Mod::new()),
-        "Semicolon" => (Key::Semicolon, Mod::new()),
-        "Slash" => (Key::Slash, Mod::new()),
-        "Period" | "NumpadDecimal" => (Key::Period, Mod::new()),
-        "Comma" => (Key::Comma, Mod::new()),
-        "Pause" => (Key::Pause, Mod::new()),
-        "ContextMenu" => (Key::Menu, Mod::new()),
-        "Backquote" => (Key::Backtick, Mod::new()),
-        "F1" => (Key::F1, Mod::new()),
-        "F2" => (Key::F2, Mod::new()),
-        "F3" => (Key::F3, Mod::new()),
-        "F4" => (Key::F4, Mod::new()),
-        "F5" => (Key::F5, Mod::new()),
-        "F6" => (Key::F6, Mod::new()),
-        "F7" => (Key::F7, Mod::new()),
-        "F8" => (Key::F8, Mod::new()),
-        "F9" => (Key::F9, Mod::new()),
-        "F10" => (Key::F10, Mod::new()),
-        "F11" => (Key::F11, Mod::new()),
-        "F12" => (Key::F12, Mod::new()),
-        "NumpadDivide" => (Key::Slash, Mod::new()),
-        "NumpadMultiply" => (Key::Eight, Mod::new().add_shift()),
-        "NumpadSubtract" => (Key::Minus, Mod::new()),
-        "NumpadAdd" => (Key::Equal, Mod::new().add_shift()),
-        "NumLock" => (Key::Compose, Mod::new()),
-        "CapsLock" => (Key::CapsLock, Mod::new()),
-        "ScrollLock" => (Key::Compose, Mod::new()),
-        "ShiftLeft" => (Key::LShift, Mod::new()),
-        "ShiftRight" => (Key::RShift, Mod::new()),
-        "" | "ControlLeft" | "MetaLeft" => (Key::LCtrl, Mod::new()),
-        "ControlRight" | "MetaRight" => (Key::RCtrl, Mod::new()),
-        "OSLeft" | "OSRight" => (Key::Env, Mod::new()),
-        "AltLeft" => (Key::LAlt, Mod::new()),
-        "AltRight" => (Key::RAlt, Mod::new()),
-        _x => return None,
+/// Lock the pointer to the element that input is scoped to (or the page), or
+/// release it.
+pub(crate) fn set_pointer_lock(locked: bool) {
+    let document = match web_sys::window().and_then(|w| w.document()) {
+        Some(document) => document,
+        None => return,
     };
-    Some(key)
+    if !locked {
+        document.exit_pointer_lock();
+    } else if let Some(ref element) = state().element {
+        element.request_pointer_lock();
+    } else if let Some(root) = document.document_element() {
+        root.request_pointer_lock();
+    }
 }
 
 /// Convert into pixels.
@@ -265,8 +469,28 @@ pub(crate) fn delta(mode: u32, value: f32) -> f32 {
     }
 }
 
-/// One type future initialization for key presses and mouse events.
-pub(crate) fn init() {
+/// One type future initialization for key presses and mouse events, from the
+/// whole window or only from `element`.
+pub(crate) fn init(config: Config, element: Option<HtmlElement>) {
+    let in_element = element.is_some();
+    state().queue.configure(config);
+    state().element = element;
+    let time = crate::clock::now();
+    let touchscreen = web_sys::window().unwrap().navigator().max_touch_points();
+    for &(id, name, kind) in &[
+        (KEYBOARD, "Keyboard", DeviceKind::Keyboard),
+        (MOUSE, "Mouse", DeviceKind::Mouse),
+        (TOUCHSCREEN, "Touchscreen", DeviceKind::Touchscreen),
+    ] {
+        if id == TOUCHSCREEN && touchscreen == 0 {
+            continue;
+        }
+        let input = Input::DeviceAdded(Device::new(id, name.to_string(), kind));
+        state()
+            .queue
+            .push(crate::Event::new(time, input).with_device(id));
+    }
+
     let localized_input = web_sys::window()
         .unwrap()
         .document()
@@ -302,103 +526,143 @@ pub(crate) fn init() {
         .append_child(&localized_input)
         .unwrap();
     let localized_input: EventTarget = localized_input.into();
-    let focus: Closure<dyn Fn()> = Closure::wrap(Box::new(move || {
-        let localized_input: HtmlElement = web_sys::window()
-            .unwrap()
-            .document()
-            .unwrap()
-            .get_element_by_id("rust_crate_human__")
-            .unwrap()
-            .dyn_into()
+    // With an element, pointer listeners are attached to it, and keyboard
+    // listeners to the hidden text input (which has focus while the element
+    // is active), so that they don't see the rest of the page's events.
+    let scope: EventTarget = match state().element {
+        Some(ref element) => element.clone().into(),
+        None => web_sys::window().unwrap().into(),
+    };
+    let key_scope: EventTarget = if in_element {
+        localized_input.clone()
+    } else {
+        web_sys::window().unwrap().into()
+    };
+    // Take focus back whenever it's lost to the page itself.  An element
+    // captures focus when it's clicked or touched instead.
+    if !in_element {
+        let blur: Closure<dyn Fn()> = Closure::wrap(Box::new(capture_focus));
+        localized_input
+            .add_event_listener_with_callback(
+                "blur",
+                blur.as_ref().unchecked_ref(),
+            )
             .unwrap();
-        localized_input.focus().unwrap();
-    }));
-    let focus = focus.into_js_value();
-    let blur: Closure<dyn Fn()> = Closure::wrap(Box::new(move || {
         web_sys::window()
             .unwrap()
-            .set_timeout_with_callback(&focus.as_ref().unchecked_ref())
+            .add_event_listener_with_callback(
+                "focus",
+                blur.as_ref().unchecked_ref(),
+            )
             .unwrap();
-    }));
+        blur.forget();
+    }
+
+    let input: Closure<dyn Fn(InputEvent)> =
+        Closure::wrap(Box::new(move |event: InputEvent| {
+            let localized_input = text_input().unwrap();
+            let value = localized_input.value();
+            if event.is_composing() {
+                // Browsers send an `input` event after every
+                // `compositionupdate`, once the pre-edit text and the cursor
+                // within it have been updated.
+                let start = localized_input.selection_start().ok().flatten();
+                let end = localized_input.selection_end().ok().flatten();
+                let start =
+                    start.map_or(value.len(), |s| byte_offset(&value, s));
+                let end = end.map_or(start, |e| byte_offset(&value, e));
+                let input = Input::CompositionUpdate(value, start..end);
+                send(&event, KEYBOARD, input);
+            } else if !value.is_empty() {
+                localized_input.set_value("");
+                // Pasted text never gets here, see the `paste` listener.
+                send(&event, KEYBOARD, Input::Text(value));
+            }
+            event.stop_propagation();
+        }));
     localized_input
         .add_event_listener_with_callback(
-            "blur",
-            &blur.as_ref().unchecked_ref(),
+            "input",
+            input.as_ref().unchecked_ref(),
         )
         .unwrap();
-    web_sys::window()
-        .unwrap()
+    input.forget();
+
+    let composition_start: Closure<dyn Fn(CompositionEvent)> =
+        Closure::wrap(Box::new(move |event: CompositionEvent| {
+            text_input().unwrap().set_value("");
+            send(&event, KEYBOARD, Input::CompositionStart);
+            event.stop_propagation();
+        }));
+    localized_input
         .add_event_listener_with_callback(
-            "focus",
-            &blur.as_ref().unchecked_ref(),
+            "compositionstart",
+            composition_start.as_ref().unchecked_ref(),
         )
         .unwrap();
-    blur.forget();
-
-    let input: Closure<dyn Fn(InputEvent)> =
-        Closure::wrap(Box::new(move |event: InputEvent| {
-            if !event.is_composing() {
-                let localized_input = web_sys::window()
-                    .unwrap()
-                    .document()
-                    .unwrap()
-                    .get_element_by_id("rust_crate_human__")
-                    .unwrap();
-                let localized_input: HtmlInputElement =
-                    localized_input.dyn_into().unwrap();
-                let value = localized_input.value();
-                localized_input.set_value("");
-                let mut char_iter = value.chars();
-                'a: while let Some(ref waker) = state().waker {
-                    if let Some(c) = char_iter.next() {
-                        state().input = Some(Input::Text(c));
-                        waker.wake_by_ref();
-                    } else {
-                        break 'a;
-                    }
-                }
+    composition_start.forget();
+
+    let composition_end: Closure<dyn Fn(CompositionEvent)> =
+        Closure::wrap(Box::new(move |event: CompositionEvent| {
+            // Some browsers send a final `input` event afterwards and some
+            // don't, so send the committed text now and clear it.
+            text_input().unwrap().set_value("");
+            let text = event.data().unwrap_or_default();
+            send(&event, KEYBOARD, Input::CompositionEnd(text.clone()));
+            if !text.is_empty() {
+                send(&event, KEYBOARD, Input::Text(text));
             }
             event.stop_propagation();
         }));
     localized_input
         .add_event_listener_with_callback(
-            "input",
-            &input.as_ref().unchecked_ref(),
+            "compositionend",
+            composition_end.as_ref().unchecked_ref(),
         )
         .unwrap();
-    input.forget();
+    composition_end.forget();
+
+    #[allow(trivial_casts)] // Actually needed here.
+    let paste: Closure<dyn Fn(ClipboardEvent)> =
+        Closure::wrap(Box::new(move |event: ClipboardEvent| {
+            let text = event
+                .clipboard_data()
+                .and_then(|data| data.get_data("text/plain").ok())
+                .unwrap_or_default();
+            if !text.is_empty() {
+                send(&event, KEYBOARD, Input::Paste(text));
+            }
+            // Don't also paste into the text input.
+            event.stop_propagation();
+            event.prevent_default();
+        }));
+    key_scope
+        .add_event_listener_with_callback(
+            "paste",
+            paste.as_ref().unchecked_ref(),
+        )
+        .unwrap();
+    paste.forget();
 
     #[allow(trivial_casts)] // Actually needed here.
     let key_down: Closure<dyn Fn(KeyboardEvent)> =
         Closure::wrap(Box::new(move |event: KeyboardEvent| {
-            // If a input is being `.await`ed, wake the waiting thread.
-            if let Some(ref waker) = state().waker {
-                // Set future to complete.
-                let mut sys_mods = false;
-                if let Some(mut keycode) = keycode(&event.code()) {
-                    sys_mods = key_modifier(&event, &mut keycode.1);
-                    if key_down_state(keycode.0) {
-                        state().input =
-                            Some(Input::Key(keycode.1, keycode.0, true));
-                    } else {
-                        state().input = None;
-                    }
-                } else {
-                    state().input = None;
-                }
-
-                // Prevent web browser from also processing the input.
-                event.stop_propagation();
-                if !is_printing(&event.code()) || sys_mods {
-                    event.prevent_default();
-                }
+            let mut sys_mods = false;
+            if let Some(key) = Key::from_dom_code(&event.code()) {
+                let mods = key_modifier(&event);
+                sys_mods = mods.ctrl() || mods.alt() || mods.super_key();
+                send_key(&event, mods, key, true);
+            }
 
-                // Wake the keyboard future.
-                waker.wake_by_ref();
+            // Prevent web browser from also processing the input, except for
+            // clipboard shortcuts, so that the `paste` listener runs.
+            event.stop_propagation();
+            let clipboard = is_clipboard(&event);
+            if (!is_printing(&event.code()) || sys_mods) && !clipboard {
+                event.prevent_default();
             }
         }));
-    web_sys::window()
-        .unwrap()
+    key_scope
         .add_event_listener_with_callback(
             "keydown",
             key_down.as_ref().unchecked_ref(),
@@ -409,34 +673,20 @@ pub(crate) fn init() {
     #[allow(trivial_casts)] // Actually needed here.
     let key_up: Closure<dyn Fn(KeyboardEvent)> =
         Closure::wrap(Box::new(move |event: KeyboardEvent| {
-            // If a input is being `.await`ed, wake the waiting thread.
-            if let Some(ref waker) = state().waker {
-                // Set future to complete.
-                let mut sys_mods = false;
-                if let Some(mut keycode) = keycode(&event.code()) {
-                    sys_mods = key_modifier(&event, &mut keycode.1);
-                    if key_up_state(keycode.0) {
-                        state().input =
-                            Some(Input::Key(keycode.1, keycode.0, false));
-                    } else {
-                        state().input = None;
-                    }
-                } else {
-                    state().input = None;
-                }
-
-                // Prevent web browser from also processing the input.
-                event.stop_propagation();
-                if !is_printing(&event.code()) || sys_mods {
-                    event.prevent_default();
-                }
+            let mut sys_mods = false;
+            if let Some(key) = Key::from_dom_code(&event.code()) {
+                let mods = key_modifier(&event);
+                sys_mods = mods.ctrl() || mods.alt() || mods.super_key();
+                send_key(&event, mods, key, false);
+            }
 
-                // Wake the keyboard future.
-                waker.wake_by_ref();
+            // Prevent web browser from also processing the input.
+            event.stop_propagation();
+            if !is_printing(&event.code()) || sys_mods {
+                event.prevent_default();
             }
         }));
-    web_sys::window()
-        .unwrap()
+    key_scope
         .add_event_listener_with_callback(
             "keyup",
             key_up.as_ref().unchecked_ref(),
@@ -445,31 +695,30 @@ pub(crate) fn init() {
     key_up.forget();
 
     let localized_input: HtmlElement = localized_input.dyn_into().unwrap();
-    localized_input.focus().unwrap();
+    if !in_element {
+        localized_input.focus().unwrap();
+    }
 
     #[allow(trivial_casts)] // Actually needed here.
     let mouse_down: Closure<dyn Fn(MouseEvent)> =
         Closure::wrap(Box::new(move |event: MouseEvent| {
+            if in_element {
+                capture_focus();
+            }
             let mods = ptr_modifier(&event);
 
-            // If a input is being `.await`ed, wake the waiting thread.
-            if let Some(ref waker) = state().waker {
-                // Set future to complete.
-                state().input = match event.button() {
-                    0 => Some(Input::Click(mods, Btn::Left, true)),
-                    1 => Some(Input::Click(mods, Btn::Middle, true)),
-                    2 => Some(Input::Click(mods, Btn::Right, true)),
-                    3 => Some(Input::Click(mods, Btn::Back, true)),
-                    4 => Some(Input::Click(mods, Btn::Next, true)),
-                    _ => Some(Input::Click(mods, Btn::Extra, true)),
-                };
-
-                // Wake the keyboard future.
-                waker.wake_by_ref();
-            }
+            let btn = match event.button() {
+                0 => Btn::Left,
+                1 => Btn::Middle,
+                2 => Btn::Right,
+                3 => Btn::Back,
+                4 => Btn::Next,
+                _ => Btn::Extra,
+            };
+            state().buttons |= 1 << btn as u8;
+            send(&event, MOUSE, Input::Click(mods, btn, true));
         }));
-    web_sys::window()
-        .unwrap()
+    scope
         .add_event_listener_with_callback(
             "mousedown",
             mouse_down.as_ref().unchecked_ref(),
@@ -482,24 +731,23 @@ pub(crate) fn init() {
         Closure::wrap(Box::new(move |event: MouseEvent| {
             let mods = ptr_modifier(&event);
 
-            // If a input is being `.await`ed, wake the waiting thread.
-            if let Some(ref waker) = state().waker {
-                // Set future to complete.
-                state().input = match event.button() {
-                    0 => Some(Input::Click(mods, Btn::Left, false)),
-                    1 => Some(Input::Click(mods, Btn::Middle, false)),
-                    2 => Some(Input::Click(mods, Btn::Right, false)),
-                    3 => Some(Input::Click(mods, Btn::Back, false)),
-                    4 => Some(Input::Click(mods, Btn::Next, false)),
-                    _ => Some(Input::Click(mods, Btn::Extra, false)),
-                };
-
-                // Wake the keyboard future.
-                waker.wake_by_ref();
+            let btn = match event.button() {
+                0 => Btn::Left,
+                1 => Btn::Middle,
+                2 => Btn::Right,
+                3 => Btn::Back,
+                4 => Btn::Next,
+                _ => Btn::Extra,
+            };
+            // Only for buttons that were pressed inside the element.
+            let bit = 1 << btn as u8;
+            if state().buttons & bit == 0 {
+                return;
             }
+            state().buttons &= !bit;
+            send(&event, MOUSE, Input::Click(mods, btn, false));
         }));
-    web_sys::window()
-        .unwrap()
+    scope
         .add_event_listener_with_callback(
             "mouseup",
             mouse_up.as_ref().unchecked_ref(),
@@ -510,15 +758,11 @@ pub(crate) fn init() {
     #[allow(trivial_casts)] // Actually needed here.
     let context_menu: Closure<dyn Fn(Event)> =
         Closure::wrap(Box::new(move |event: Event| {
-            // If a input is being `.await`ed,
-            if state().waker.is_some() {
-                // Ignore these events, and don't let the browser process them.
-                event.stop_propagation();
-                event.prevent_default();
-            }
+            // Ignore these events, and don't let the browser process them.
+            event.stop_propagation();
+            event.prevent_default();
         }));
-    web_sys::window()
-        .unwrap()
+    scope
         .add_event_listener_with_callback(
             "contextmenu",
             context_menu.as_ref().unchecked_ref(),
@@ -529,47 +773,20 @@ pub(crate) fn init() {
     #[allow(trivial_casts)] // Actually needed here.
     let wheel: Closure<dyn Fn(WheelEvent)> =
         Closure::wrap(Box::new(move |event: WheelEvent| {
-            let mods = ptr_modifier(&event);
-            let width = web_sys::window()
-                .unwrap()
-                .inner_width()
-                .unwrap()
-                .as_f64()
-                .unwrap();
-            let width = width as f32;
-            let delta_mode = event.delta_mode();
-
-            // If a input is being `.await`ed, wake the waiting thread.
-            if let Some(ref waker) = state().waker {
-                let x = delta(delta_mode, event.delta_x() as f32) / width;
-                if x.abs() > f32::EPSILON {
-                    state().input = Some(Input::ScrollX(mods, x));
-
-                    // Wake the keyboard future.
-                    waker.wake_by_ref();
-                }
-            }
-            // If a input is being `.await`ed, wake the waiting thread.
-            if let Some(ref waker) = state().waker {
-                let y = delta(delta_mode, event.delta_y() as f32) / width;
-                if y.abs() > f32::EPSILON {
-                    state().input = Some(Input::ScrollY(mods, y));
-
-                    // Wake the keyboard future.
-                    waker.wake_by_ref();
-                }
-            }
+            send_scroll(&event);
 
             // Prevent zoom and scroll.
             event.stop_propagation();
             event.prevent_default();
         }));
-    web_sys::window()
-        .unwrap()
+    // Not passive, so that scrolling the page can be prevented.
+    let options = AddEventListenerOptions::new();
+    options.set_passive(false);
+    scope
         .add_event_listener_with_callback_and_add_event_listener_options(
             "wheel",
             wheel.as_ref().unchecked_ref(),
-            &AddEventListenerOptions::new().passive(false),
+            &options,
         )
         .unwrap();
     wheel.forget();
@@ -577,37 +794,20 @@ pub(crate) fn init() {
     #[allow(trivial_casts)] // Actually needed here.
     let mouse_move: Closure<dyn Fn(MouseEvent)> =
         Closure::wrap(Box::new(move |event: MouseEvent| {
-            let width = web_sys::window()
-                .unwrap()
-                .inner_width()
-                .unwrap()
-                .as_f64()
-                .unwrap();
-            let width = width as f32 - 1.0;
-
-            // If a input is being `.await`ed, wake the waiting thread.
-            if let Some(ref waker) = state().waker {
-                let x = event.client_x() as f32 / width;
-                if x.abs() > f32::EPSILON {
-                    state().input = Some(Input::PointerX(x));
-
-                    // Wake the keyboard future.
-                    waker.wake_by_ref();
-                }
+            // Motion outside of the element is only seen while dragging (the
+            // pointer is captured by the element), or while the pointer is
+            // locked to it.  The pointer doesn't move while it's locked.
+            if !state().pointer_lock {
+                let (x, y) = position(event.client_x(), event.client_y());
+                send(&event, MOUSE, Input::PointerMove { x, y });
             }
-            // If a input is being `.await`ed, wake the waiting thread.
-            if let Some(ref waker) = state().waker {
-                let y = event.client_y() as f32 / width;
-                if y.abs() > f32::EPSILON {
-                    state().input = Some(Input::PointerY(y));
-
-                    // Wake the keyboard future.
-                    waker.wake_by_ref();
-                }
+            let dx = event.movement_x() as f32;
+            let dy = event.movement_y() as f32;
+            if dx != 0.0 || dy != 0.0 {
+                send(&event, MOUSE, Input::PointerDelta { dx, dy });
             }
         }));
-    web_sys::window()
-        .unwrap()
+    scope
         .add_event_listener_with_callback(
             "mousemove",
             mouse_move.as_ref().unchecked_ref(),
@@ -616,21 +816,180 @@ pub(crate) fn init() {
     mouse_move.forget();
 
     #[allow(trivial_casts)] // Actually needed here.
-    let mouse_leave: Closure<dyn Fn(MouseEvent)> =
-        Closure::wrap(Box::new(move |_event: MouseEvent| {
-            // If a input is being `.await`ed, wake the waiting thread.
-            if let Some(ref waker) = state().waker {
-                state().input = Some(Input::PointerLeave);
-                // Wake the keyboard future.
-                waker.wake_by_ref();
+    let pointer_lock: Closure<dyn Fn(Event)> =
+        Closure::wrap(Box::new(move |event: Event| {
+            let locked = web_sys::window()
+                .unwrap()
+                .document()
+                .unwrap()
+                .pointer_lock_element()
+                .is_some();
+            state().pointer_lock = locked;
+            send(&event, MOUSE, Input::PointerLock(locked));
+        }));
+    web_sys::window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .add_event_listener_with_callback(
+            "pointerlockchange",
+            pointer_lock.as_ref().unchecked_ref(),
+        )
+        .unwrap();
+    pointer_lock.forget();
+
+    #[allow(trivial_casts)] // Actually needed here.
+    let mouse_cross: Closure<dyn Fn(MouseEvent)> =
+        Closure::wrap(Box::new(move |event: MouseEvent| {
+            // Only moving into or out of the element (or the window), not
+            // between elements inside it.
+            if !crossed(&event) {
+                return;
+            }
+            let input = if event.type_() == "mouseover" {
+                Input::PointerEnter
+            } else {
+                Input::PointerLeave
+            };
+            send(&event, MOUSE, input);
+        }));
+    for &name in &["mouseover", "mouseout"] {
+        scope
+            .add_event_listener_with_callback(
+                name,
+                mouse_cross.as_ref().unchecked_ref(),
+            )
+            .unwrap();
+    }
+    mouse_cross.forget();
+
+    #[allow(trivial_casts)] // Actually needed here.
+    let window_focus: Closure<dyn Fn(Event)> =
+        Closure::wrap(Box::new(move |event: Event| {
+            // Only for the window (or the hidden text input) itself.
+            if event.target() != event.current_target() {
+                return;
             }
+            if event.type_() == "focus" {
+                send_window(&event, Input::FocusGained);
+            } else {
+                // Keys and buttons released while the window doesn't have
+                // focus are never seen, so the listener releases them on focus
+                // loss.
+                let state = state();
+                state.keyboard = KeyboardState::new();
+                state.buttons = 0;
+                send_window(&event, Input::FocusLost);
+            }
+        }));
+    // With an element, focus is the hidden text input's focus.
+    let focus_target: EventTarget = if in_element {
+        localized_input.clone().into()
+    } else {
+        web_sys::window().unwrap().into()
+    };
+    for &name in &["focus", "blur"] {
+        focus_target
+            .add_event_listener_with_callback(
+                name,
+                window_focus.as_ref().unchecked_ref(),
+            )
+            .unwrap();
+    }
+    window_focus.forget();
+
+    #[allow(trivial_casts)] // Actually needed here.
+    let visibility: Closure<dyn Fn(Event)> =
+        Closure::wrap(Box::new(move |event: Event| {
+            let hidden =
+                web_sys::window().unwrap().document().unwrap().hidden();
+            send_window(&event, Input::Visibility(!hidden));
         }));
     web_sys::window()
+        .unwrap()
+        .document()
         .unwrap()
         .add_event_listener_with_callback(
-            "mouseout",
-            mouse_leave.as_ref().unchecked_ref(),
+            "visibilitychange",
+            visibility.as_ref().unchecked_ref(),
         )
         .unwrap();
-    mouse_leave.forget();
+    visibility.forget();
+
+    let touch_inputs: [(&str, TouchInput); 4] = [
+        ("touchstart", Input::TouchStart),
+        ("touchmove", Input::TouchMove),
+        ("touchend", Input::TouchEnd),
+        ("touchcancel", Input::TouchCancel),
+    ];
+    for &(name, input) in touch_inputs.iter() {
+        #[allow(trivial_casts)] // Actually needed here.
+        let touch: Closure<dyn Fn(TouchEvent)> =
+            Closure::wrap(Box::new(move |event: TouchEvent| {
+                if in_element && name == "touchstart" {
+                    capture_focus();
+                }
+                send_touches(&event, input);
+                // Prevent scrolling, but not the mouse events that browsers
+                // send for taps.
+                if name == "touchmove" {
+                    event.prevent_default();
+                }
+            }));
+        let options = AddEventListenerOptions::new();
+        options.set_passive(false);
+        scope
+            .add_event_listener_with_callback_and_add_event_listener_options(
+                name,
+                touch.as_ref().unchecked_ref(),
+                &options,
+            )
+            .unwrap();
+        touch.forget();
+    }
+
+    for &name in &["pointerover", "pointerdown", "pointermove", "pointerup"] {
+        #[allow(trivial_casts)] // Actually needed here.
+        let pen: Closure<dyn Fn(PointerEvent)> =
+            Closure::wrap(Box::new(move |event: PointerEvent| {
+                // Keep sending mouse motion and releases from outside of the
+                // element while dragging.
+                if in_element
+                    && event.type_() == "pointerdown"
+                    && event.pointer_type() == "mouse"
+                {
+                    if let Some(ref element) = state().element {
+                        let _ = element.set_pointer_capture(event.pointer_id());
+                    }
+                }
+                send_pen(&event);
+            }));
+        scope
+            .add_event_listener_with_callback(
+                name,
+                pen.as_ref().unchecked_ref(),
+            )
+            .unwrap();
+        pen.forget();
+    }
+
+    #[allow(trivial_casts)] // Actually needed here.
+    let pen_leave: Closure<dyn Fn(PointerEvent)> =
+        Closure::wrap(Box::new(move |event: PointerEvent| {
+            // Moving out of the element or window (rather than between
+            // elements inside it), or taken over by the browser.
+            let left = event.type_() == "pointercancel" || crossed(&event);
+            if event.pointer_type() == "pen" && left {
+                send(&event, PEN, Input::PenLeave);
+            }
+        }));
+    for &name in &["pointerout", "pointercancel"] {
+        scope
+            .add_event_listener_with_callback(
+                name,
+                pen_leave.as_ref().unchecked_ref(),
+            )
+            .unwrap();
+    }
+    pen_leave.forget();
 }
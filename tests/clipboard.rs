@@ -0,0 +1,33 @@
+//! The in-memory clipboard.
+
+use std::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use human::clipboard;
+
+/// Poll a future that's expected to be ready immediately.
+fn now<T>(future: impl Future<Output = T>) -> T {
+    let mut cx = Context::from_waker(Waker::noop());
+    match pin!(future).poll(&mut cx) {
+        Poll::Ready(value) => value,
+        Poll::Pending => panic!("future is pending"),
+    }
+}
+
+#[test]
+fn memory() {
+    let memory = clipboard::use_memory();
+    assert_eq!(now(clipboard::read_text()), Ok(String::new()));
+    assert_eq!(now(clipboard::write_text("copied 👩‍👧")), Ok(()));
+    assert_eq!(now(clipboard::read_text()), Ok("copied 👩‍👧".to_string()));
+
+    // Nested uses get their own clipboard, and restore the outer one.
+    let inner = clipboard::use_memory();
+    assert_eq!(now(clipboard::read_text()), Ok(String::new()));
+    drop(inner);
+    assert_eq!(now(clipboard::read_text()), Ok("copied 👩‍👧".to_string()));
+    drop(memory);
+}
@@ -0,0 +1,399 @@
+//! Decoding of recorded evdev streams (64-bit little endian `input_event`s).
+
+#![cfg(all(
+    target_os = "linux",
+    target_pointer_width = "64",
+    target_endian = "little"
+))]
+
+use std::time::Duration;
+
+use human::{
+    Btn, DeviceId, EvdevDecoder, Event, Input, Key,
+    KeyAction::{Press, Release, Repeat},
+    PenTool, ScrollSource,
+};
+
+const FIRST: DeviceId = DeviceId::new(0);
+const SECOND: DeviceId = DeviceId::new(1);
+
+const KEYBOARD: &[u8] = include_bytes!("fixtures/keyboard.bin");
+const MOUSE: &[u8] = include_bytes!("fixtures/mouse.bin");
+const DROPPED: &[u8] = include_bytes!("fixtures/dropped.bin");
+
+/// Encode an `input_event` (with a zero timestamp).
+fn record(ty: u16, code: u16, value: i32) -> Vec<u8> {
+    record_at((0, 0), ty, code, value)
+}
+
+/// Encode an `input_event` with a timestamp (seconds and microseconds).
+fn record_at(time: (i64, i64), ty: u16, code: u16, value: i32) -> Vec<u8> {
+    let mut bytes = time.0.to_le_bytes().to_vec();
+    bytes.extend_from_slice(&time.1.to_le_bytes());
+    bytes.extend_from_slice(&ty.to_le_bytes());
+    bytes.extend_from_slice(&code.to_le_bytes());
+    bytes.extend_from_slice(&value.to_le_bytes());
+    bytes
+}
+
+fn decode(decoder: &mut EvdevDecoder) -> Vec<Input> {
+    std::iter::from_fn(|| decoder.pop())
+        .map(Event::into_input)
+        .collect()
+}
+
+#[test]
+fn keyboard() {
+    let mut decoder = EvdevDecoder::new();
+    decoder.push(FIRST, KEYBOARD);
+    let inputs = decode(&mut decoder);
+
+    assert_eq!(inputs.len(), 8);
+    assert!(
+        matches!(inputs[0], Input::Key(m, Key::LShift, Press) if m.shift())
+    );
+    assert!(matches!(inputs[1], Input::Key(m, Key::A, Press) if m.shift()));
+    assert!(matches!(inputs[2], Input::Key(m, Key::A, Repeat) if m.shift()));
+    assert!(matches!(inputs[3], Input::Key(_, Key::A, Repeat)));
+    assert!(matches!(inputs[4], Input::Key(m, Key::A, Release) if m.shift()));
+    assert!(
+        matches!(inputs[5], Input::Key(m, Key::LShift, Release) if m.none())
+    );
+    assert!(matches!(inputs[6], Input::Key(m, Key::Space, Press) if m.none()));
+    assert!(
+        matches!(inputs[7], Input::Key(m, Key::Space, Release) if m.none())
+    );
+}
+
+#[test]
+fn split_records() {
+    let mut decoder = EvdevDecoder::new();
+    for byte in KEYBOARD.chunks(7) {
+        decoder.push(FIRST, byte);
+    }
+    let inputs = decode(&mut decoder);
+
+    assert_eq!(inputs.len(), 8);
+    assert!(matches!(inputs[1], Input::Key(m, Key::A, Press) if m.shift()));
+}
+
+#[test]
+fn mouse() {
+    let mut decoder = EvdevDecoder::new();
+    decoder.push(FIRST, MOUSE);
+    let inputs = decode(&mut decoder);
+
+    assert_eq!(inputs.len(), 8);
+    assert!(matches!(
+        inputs[0],
+        Input::PointerMove { x, y } if x == 0.55 && y == 0.45
+    ));
+    assert!(matches!(
+        inputs[1],
+        Input::PointerDelta { dx, dy } if dx == 96.0 && dy == -54.0
+    ));
+    assert!(matches!(inputs[2], Input::Click(m, Btn::Left, true) if m.none()));
+    assert!(matches!(inputs[3], Input::Click(_, Btn::Left, false)));
+    assert!(matches!(
+        inputs[4],
+        Input::Scroll(_, s)
+            if s.detents() == (0, -1) && s.delta() == (0.0, -48.0)
+            && s.source() == ScrollSource::Wheel
+    ));
+    assert!(matches!(
+        inputs[5],
+        Input::Scroll(_, s)
+            if s.detents() == (-1, 0) && s.delta() == (-48.0, 0.0)
+    ));
+    assert!(matches!(inputs[6], Input::Click(_, Btn::Back, true)));
+    assert!(matches!(inputs[7], Input::Click(_, Btn::Back, false)));
+}
+
+#[test]
+fn hi_res_scroll() {
+    let mut decoder = EvdevDecoder::new();
+    // Half a detent, then the rest of it.
+    decoder.push(FIRST, &record(0x02, 0x0B, 60));
+    decoder.push(FIRST, &record(0x00, 0x00, 0));
+    decoder.push(FIRST, &record(0x02, 0x0B, 60));
+    decoder.push(FIRST, &record(0x02, 0x08, 1));
+    decoder.push(FIRST, &record(0x00, 0x00, 0));
+    let inputs = decode(&mut decoder);
+
+    assert_eq!(inputs.len(), 2);
+    assert!(matches!(
+        inputs[0],
+        Input::Scroll(_, s)
+            if s.detents() == (0, 0) && s.delta() == (0.0, -24.0)
+            && s.source() == ScrollSource::Continuous
+    ));
+    assert!(matches!(
+        inputs[1],
+        Input::Scroll(_, s)
+            if s.detents() == (0, -1) && s.delta() == (0.0, -24.0)
+    ));
+}
+
+#[test]
+fn pointer_lock() {
+    let mut decoder = EvdevDecoder::new();
+    decoder.set_pointer_lock(true);
+    decoder.push(FIRST, MOUSE);
+    let inputs = decode(&mut decoder);
+
+    assert_eq!(inputs.len(), 7);
+    assert!(matches!(
+        inputs[0],
+        Input::PointerDelta { dx, dy } if dx == 96.0 && dy == -54.0
+    ));
+
+    // The pointer didn't move while it was locked.
+    decoder.set_pointer_lock(false);
+    decoder.push(FIRST, &MOUSE[..24 * 3]);
+    let inputs = decode(&mut decoder);
+    assert!(matches!(
+        inputs[0],
+        Input::PointerMove { x, y } if x == 0.55 && y == 0.45
+    ));
+}
+
+#[test]
+fn dropped() {
+    let mut decoder = EvdevDecoder::new();
+    decoder.push(FIRST, DROPPED);
+    let inputs = decode(&mut decoder);
+
+    assert_eq!(inputs.len(), 4);
+    assert!(matches!(
+        inputs[0],
+        Input::PointerMove { x, y: _ } if x == 970.0 / 1920.0
+    ));
+    assert!(matches!(inputs[1], Input::PointerDelta { dx, .. } if dx == 10.0));
+    assert!(matches!(
+        inputs[2],
+        Input::PointerMove { x, y: _ } if x == 1010.0 / 1920.0
+    ));
+    assert!(matches!(inputs[3], Input::PointerDelta { dx, .. } if dx == 40.0));
+}
+
+#[test]
+fn modifiers_across_devices() {
+    let mut decoder = EvdevDecoder::new();
+    // Hold shift on the keyboard, then click on the mouse.
+    decoder.push(FIRST, &KEYBOARD[..24 * 2]);
+    decoder.push(SECOND, &MOUSE[24 * 4..24 * 6]);
+    // Unplug the keyboard, then click again.
+    decoder.remove(FIRST);
+    decoder.push(SECOND, &MOUSE[24 * 4..24 * 6]);
+    let inputs = decode(&mut decoder);
+
+    assert_eq!(inputs.len(), 3);
+    assert!(matches!(inputs[1], Input::Click(m, Btn::Left, true) if m.shift()));
+    assert!(matches!(inputs[2], Input::Click(m, Btn::Left, true) if m.none()));
+}
+
+#[test]
+fn super_and_locks() {
+    let mut decoder = EvdevDecoder::new();
+    // Left meta + c, then caps lock on, a, and caps lock turned off by LED.
+    for &(ty, code, value) in &[
+        (0x01, 125, 1),
+        (0x01, 46, 1),
+        (0x01, 46, 0),
+        (0x01, 125, 0),
+        (0x01, 58, 1),
+        (0x01, 58, 0),
+        (0x01, 30, 1),
+        (0x11, 0x01, 0),
+        (0x01, 30, 0),
+    ] {
+        decoder.push(FIRST, &record(ty, code, value));
+    }
+    let inputs = decode(&mut decoder);
+
+    assert_eq!(inputs.len(), 6);
+    assert!(matches!(inputs[0], Input::Key(m, Key::C, Press) if m.super_key()));
+    assert!(
+        matches!(inputs[1], Input::Key(m, Key::C, Release) if m.super_key())
+    );
+    assert!(
+        matches!(inputs[2], Input::Key(m, Key::Caps, Press) if m.caps_lock())
+    );
+    assert!(matches!(inputs[4], Input::Key(m, Key::A, Press) if m.caps_lock()));
+    assert!(
+        matches!(inputs[5], Input::Key(m, Key::A, Release) if !m.caps_lock())
+    );
+}
+
+#[test]
+fn altgr() {
+    let mut decoder = EvdevDecoder::new();
+    // Right alt + e, then left alt + e.
+    for &(ty, code, value) in &[
+        (0x01, 100, 1),
+        (0x01, 18, 1),
+        (0x01, 18, 0),
+        (0x01, 100, 0),
+        (0x01, 56, 1),
+        (0x01, 18, 1),
+    ] {
+        decoder.push(FIRST, &record(ty, code, value));
+    }
+    let inputs = decode(&mut decoder);
+
+    assert_eq!(inputs.len(), 6);
+    assert!(
+        matches!(inputs[1], Input::Key(m, Key::E, Press) if m.altgr() && !m.alt())
+    );
+    assert!(
+        matches!(inputs[5], Input::Key(m, Key::E, Press) if m.alt() && !m.altgr())
+    );
+}
+
+#[test]
+fn numpad() {
+    let mut decoder = EvdevDecoder::new();
+    // Keypad 1, 1 on the top row, keypad *.
+    for &code in &[79, 2, 55] {
+        decoder.push(FIRST, &record(0x01, code, 1));
+    }
+    let inputs = decode(&mut decoder);
+
+    assert_eq!(inputs.len(), 3);
+    assert!(matches!(inputs[0], Input::Key(_, Key::NumOne, Press)));
+    assert!(matches!(inputs[1], Input::Key(_, Key::One, Press)));
+    assert!(
+        matches!(inputs[2], Input::Key(m, Key::NumStar, Press) if m.none())
+    );
+}
+
+#[test]
+fn timestamps() {
+    let mut decoder = EvdevDecoder::new();
+    decoder.push(FIRST, &record_at((12, 500_000), 0x01, 30, 1));
+    decoder.push(FIRST, &record_at((12, 750_000), 0x02, 0x00, 5));
+    decoder.push(FIRST, &record_at((12, 750_000), 0x00, 0x00, 0));
+    decoder.push(FIRST, &record_at((13, 0), 0x01, 30, 0));
+    let times: Vec<Duration> = std::iter::from_fn(|| decoder.pop())
+        .map(|e| e.time())
+        .collect();
+
+    assert_eq!(
+        times,
+        [
+            Duration::from_millis(12_500),
+            Duration::from_millis(12_750),
+            Duration::from_millis(12_750),
+            Duration::from_secs(13),
+        ]
+    );
+}
+
+#[test]
+fn devices() {
+    let mut decoder = EvdevDecoder::new();
+    decoder.push(FIRST, &record(0x01, 30, 1));
+    decoder.push(SECOND, &record(0x01, 48, 1));
+    decoder.push(FIRST, &record(0x01, 30, 0));
+    let events: Vec<Event> = std::iter::from_fn(|| decoder.pop()).collect();
+
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0].device(), Some(FIRST));
+    assert_eq!(events[1].device(), Some(SECOND));
+    assert_eq!(events[2].device(), Some(FIRST));
+}
+
+#[test]
+fn multi_touch() {
+    let mut decoder = EvdevDecoder::new();
+    decoder.set_abs_range(FIRST, 0x35, 0, 1000);
+    decoder.set_abs_range(FIRST, 0x36, 0, 500);
+    decoder.set_abs_range(FIRST, 0x3A, 0, 200);
+    // Two fingers down, the first moves and lifts, then events are dropped.
+    for &(ty, code, value) in &[
+        (0x03, 0x2F, 0),
+        (0x03, 0x39, 7),
+        (0x03, 0x35, 250),
+        (0x03, 0x36, 100),
+        (0x03, 0x3A, 150),
+        (0x03, 0x2F, 1),
+        (0x03, 0x39, 8),
+        (0x03, 0x35, 500),
+        (0x03, 0x36, 250),
+        (0x00, 0x00, 0),
+        (0x03, 0x2F, 0),
+        (0x03, 0x35, 300),
+        (0x00, 0x00, 0),
+        (0x03, 0x39, -1),
+        (0x00, 0x00, 0),
+        (0x00, 0x03, 0),
+    ] {
+        decoder.push(FIRST, &record(ty, code, value));
+    }
+    let inputs = decode(&mut decoder);
+
+    assert_eq!(inputs.len(), 5);
+    assert!(matches!(inputs[0], Input::TouchStart(t) if t.id() == 7
+        && t.x() == 0.25
+        && t.y() == 0.2
+        && t.pressure() == 0.75));
+    assert!(matches!(inputs[1], Input::TouchStart(t) if t.id() == 8));
+    assert!(
+        matches!(inputs[2], Input::TouchMove(t) if t.id() == 7 && t.x() == 0.3)
+    );
+    assert!(
+        matches!(inputs[3], Input::TouchEnd(t) if t.id() == 7 && t.x() == 0.3)
+    );
+    assert!(matches!(inputs[4], Input::TouchCancel(t) if t.id() == 8));
+}
+
+#[test]
+fn pen() {
+    let mut decoder = EvdevDecoder::new();
+    decoder.set_abs_range(FIRST, 0x00, 0, 1000);
+    decoder.set_abs_range(FIRST, 0x01, 0, 500);
+    decoder.set_abs_range(FIRST, 0x18, 0, 1000);
+    // Hover in, press down with the barrel button held, then lift and leave.
+    for &(ty, code, value) in &[
+        (0x01, 0x140, 1),
+        (0x03, 0x00, 500),
+        (0x03, 0x01, 100),
+        (0x03, 0x1A, 30),
+        (0x03, 0x1B, -200),
+        (0x00, 0x00, 0),
+        (0x01, 0x14A, 1),
+        (0x01, 0x14B, 1),
+        (0x03, 0x18, 250),
+        (0x00, 0x00, 0),
+        (0x00, 0x00, 0),
+        (0x01, 0x14A, 0),
+        (0x01, 0x14B, 0),
+        (0x03, 0x18, 0),
+        (0x00, 0x00, 0),
+        (0x01, 0x140, 0),
+        (0x00, 0x00, 0),
+        (0x01, 0x141, 1),
+        (0x00, 0x00, 0),
+        (0x00, 0x03, 0),
+    ] {
+        decoder.push(FIRST, &record(ty, code, value));
+    }
+    let inputs = decode(&mut decoder);
+
+    assert_eq!(inputs.len(), 6);
+    assert!(
+        matches!(inputs[0], Input::Pen(p) if p.tool() == PenTool::Pen
+        && p.x() == 0.5
+        && p.y() == 0.2
+        && p.tilt() == (30.0, -90.0)
+        && !p.contact()
+        && p.pressure() == 0.0)
+    );
+    assert!(matches!(inputs[1], Input::Pen(p) if p.contact()
+        && p.barrel()
+        && p.pressure() == 0.25));
+    assert!(matches!(inputs[2], Input::Pen(p) if !p.contact() && !p.barrel()));
+    assert!(matches!(inputs[3], Input::PenLeave));
+    assert!(matches!(inputs[4], Input::Pen(p) if p.tool() == PenTool::Eraser));
+    assert!(matches!(inputs[5], Input::PenLeave));
+}
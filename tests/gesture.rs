@@ -0,0 +1,142 @@
+//! Touch gesture recognition.
+
+use std::{f32::consts::FRAC_PI_2, time::Duration};
+
+use human::{Direction, Event, Gesture, GestureRecognizer, Input, Touch};
+
+fn at(millis: u64, input: Input) -> Event {
+    Event::new(Duration::from_millis(millis), input)
+}
+
+fn touch(id: u32, x: f32, y: f32) -> Touch {
+    Touch::new(id, x, y, 0.5)
+}
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 0.0001
+}
+
+#[test]
+fn tap() {
+    let mut gestures = GestureRecognizer::new();
+    gestures.update(&at(1000, Input::TouchStart(touch(0, 0.5, 0.5))));
+    gestures.update(&at(1050, Input::TouchMove(touch(0, 0.51, 0.5))));
+    gestures.update(&at(1100, Input::TouchEnd(touch(0, 0.51, 0.5))));
+    assert_eq!(gestures.pop(), Some(Gesture::Tap(0.51, 0.5)));
+    assert_eq!(gestures.pop(), None);
+
+    // Held too long to be a tap.
+    gestures.update(&at(2000, Input::TouchStart(touch(1, 0.5, 0.5))));
+    gestures.update(&at(2400, Input::TouchEnd(touch(1, 0.5, 0.5))));
+    assert_eq!(gestures.pop(), None);
+
+    // Cancelled.
+    gestures.update(&at(3000, Input::TouchStart(touch(2, 0.5, 0.5))));
+    gestures.update(&at(3100, Input::TouchCancel(touch(2, 0.5, 0.5))));
+    assert_eq!(gestures.pop(), None);
+}
+
+#[test]
+fn long_press() {
+    let mut gestures = GestureRecognizer::new();
+    assert_eq!(gestures.deadline(), None);
+    gestures.update(&at(1000, Input::TouchStart(touch(0, 0.2, 0.3))));
+    assert_eq!(gestures.deadline(), Some(Duration::from_millis(1500)));
+    gestures.advance(Duration::from_millis(1499));
+    assert_eq!(gestures.pop(), None);
+    gestures.advance(Duration::from_millis(1500));
+    assert_eq!(gestures.pop(), Some(Gesture::LongPress(0.2, 0.3)));
+    assert_eq!(gestures.deadline(), None);
+    gestures.update(&at(1800, Input::TouchEnd(touch(0, 0.2, 0.3))));
+    assert_eq!(gestures.pop(), None);
+
+    // Moving cancels the long press.
+    gestures.update(&at(2000, Input::TouchStart(touch(1, 0.2, 0.3))));
+    gestures.update(&at(2100, Input::TouchMove(touch(1, 0.3, 0.3))));
+    assert_eq!(gestures.deadline(), None);
+    gestures.advance(Duration::from_millis(2600));
+    assert_eq!(gestures.pop(), None);
+}
+
+#[test]
+fn swipe() {
+    let mut gestures = GestureRecognizer::new();
+    let swipes = [
+        (0.8, 0.5, Direction::Right),
+        (0.2, 0.5, Direction::Left),
+        (0.5, 0.2, Direction::Up),
+        (0.5, 0.8, Direction::Down),
+    ];
+    for (i, &(x, y, direction)) in swipes.iter().enumerate() {
+        let t = 1000 * i as u64;
+        gestures.update(&at(t, Input::TouchStart(touch(0, 0.5, 0.5))));
+        gestures.update(&at(t + 50, Input::TouchMove(touch(0, x, y))));
+        gestures.update(&at(t + 100, Input::TouchEnd(touch(0, x, y))));
+        assert_eq!(gestures.pop(), Some(Gesture::Swipe(direction)));
+        assert_eq!(gestures.pop(), None);
+    }
+
+    // Too slow to be a swipe.
+    gestures.update(&at(9000, Input::TouchStart(touch(0, 0.5, 0.5))));
+    gestures.update(&at(9200, Input::TouchMove(touch(0, 0.7, 0.5))));
+    gestures.update(&at(11000, Input::TouchEnd(touch(0, 0.7, 0.5))));
+    assert_eq!(gestures.pop(), None);
+}
+
+#[test]
+fn two_fingers() {
+    let mut gestures = GestureRecognizer::new();
+    gestures.update(&at(1000, Input::TouchStart(touch(0, 0.4, 0.5))));
+    gestures.update(&at(1010, Input::TouchStart(touch(1, 0.6, 0.5))));
+    assert_eq!(gestures.deadline(), None);
+
+    // Spread apart.
+    gestures.update(&at(1020, Input::TouchMove(touch(1, 0.8, 0.5))));
+    assert!(matches!(gestures.pop(), Some(Gesture::Pinch(s)) if close(s, 2.0)));
+    assert!(matches!(
+        gestures.pop(),
+        Some(Gesture::Pan(x, y)) if close(x, 0.1) && close(y, 0.0)
+    ));
+    assert_eq!(gestures.pop(), None);
+
+    // Turn a quarter clockwise around the first finger.
+    gestures.update(&at(1030, Input::TouchMove(touch(1, 0.4, 0.9))));
+    assert!(matches!(gestures.pop(), Some(Gesture::Pinch(s)) if close(s, 1.0)));
+    assert!(matches!(
+        gestures.pop(),
+        Some(Gesture::Rotate(a)) if close(a, FRAC_PI_2)
+    ));
+    assert!(matches!(gestures.pop(), Some(Gesture::Pan(..))));
+    assert_eq!(gestures.pop(), None);
+
+    // Lifting the fingers isn't a tap or swipe.
+    gestures.update(&at(1040, Input::TouchEnd(touch(1, 0.4, 0.9))));
+    gestures.update(&at(1050, Input::TouchEnd(touch(0, 0.4, 0.5))));
+    assert_eq!(gestures.pop(), None);
+}
+
+#[test]
+fn logical_coordinates() {
+    // Positions in CSS pixels, as with `Coordinates::Logical`.
+    let mut gestures = GestureRecognizer::new();
+    gestures.resize(800.0, 600.0);
+
+    // Moving a few pixels is still a tap.
+    gestures.update(&at(1000, Input::TouchStart(touch(0, 400.0, 300.0))));
+    gestures.update(&at(1050, Input::TouchMove(touch(0, 405.0, 300.0))));
+    gestures.update(&at(1100, Input::TouchEnd(touch(0, 405.0, 300.0))));
+    assert_eq!(gestures.pop(), Some(Gesture::Tap(405.0, 300.0)));
+    assert_eq!(gestures.pop(), None);
+
+    // Too short to be a swipe.
+    gestures.update(&at(2000, Input::TouchStart(touch(1, 400.0, 300.0))));
+    gestures.update(&at(2050, Input::TouchMove(touch(1, 440.0, 300.0))));
+    gestures.update(&at(2100, Input::TouchEnd(touch(1, 440.0, 300.0))));
+    assert_eq!(gestures.pop(), None);
+
+    gestures.update(&at(3000, Input::TouchStart(touch(2, 400.0, 300.0))));
+    gestures.update(&at(3050, Input::TouchMove(touch(2, 400.0, 100.0))));
+    gestures.update(&at(3100, Input::TouchEnd(touch(2, 400.0, 100.0))));
+    assert_eq!(gestures.pop(), Some(Gesture::Swipe(Direction::Up)));
+    assert_eq!(gestures.pop(), None);
+}
@@ -0,0 +1,341 @@
+//! Conversions between `Key` and platform keycodes.
+
+use human::{Key, ParseError};
+
+/// Every key, in declaration order.
+const ALL: &[Key] = &[
+    Key::Esc,
+    Key::One,
+    Key::Two,
+    Key::Three,
+    Key::Four,
+    Key::Five,
+    Key::Six,
+    Key::Seven,
+    Key::Eight,
+    Key::Nine,
+    Key::Zero,
+    Key::Minus,
+    Key::Equal,
+    Key::Backslash,
+    Key::Backtick,
+    Key::Tab,
+    Key::Q,
+    Key::W,
+    Key::E,
+    Key::R,
+    Key::T,
+    Key::Y,
+    Key::U,
+    Key::I,
+    Key::O,
+    Key::P,
+    Key::BracketOpen,
+    Key::BracketClose,
+    Key::Delete,
+    Key::Caps,
+    Key::A,
+    Key::S,
+    Key::D,
+    Key::F,
+    Key::G,
+    Key::H,
+    Key::J,
+    Key::K,
+    Key::L,
+    Key::Semicolon,
+    Key::Apostrophe,
+    Key::Enter,
+    Key::LShift,
+    Key::Z,
+    Key::X,
+    Key::C,
+    Key::V,
+    Key::B,
+    Key::N,
+    Key::M,
+    Key::Comma,
+    Key::Period,
+    Key::Slash,
+    Key::RShift,
+    Key::Up,
+    Key::LCtrl,
+    Key::LAlt,
+    Key::Space,
+    Key::Thumb,
+    Key::RAlt,
+    Key::RCtrl,
+    Key::Left,
+    Key::Down,
+    Key::Right,
+    Key::Power,
+    Key::F1,
+    Key::F2,
+    Key::F3,
+    Key::F4,
+    Key::F5,
+    Key::F6,
+    Key::F7,
+    Key::F8,
+    Key::F9,
+    Key::F10,
+    Key::F11,
+    Key::F12,
+    Key::Screenshot,
+    Key::Insert,
+    Key::Next,
+    Key::Microphone,
+    Key::Web,
+    Key::Email,
+    Key::Player,
+    Key::Calculator,
+    Key::Del,
+    Key::Prev,
+    Key::Airplane,
+    Key::Scroll,
+    Key::Display,
+    Key::Han,
+    Key::Hanja,
+    Key::Kana,
+    Key::Convert,
+    Key::NonConvert,
+    Key::NumEnter,
+    Key::Mute,
+    Key::Quieter,
+    Key::Louder,
+    Key::Dimmer,
+    Key::Brighter,
+    Key::Num,
+    Key::Menu,
+    Key::Break,
+    Key::Clear,
+    Key::Pause,
+    Key::PageUp,
+    Key::Play,
+    Key::Stop,
+    Key::Home,
+    Key::PageDown,
+    Key::End,
+    Key::NumOne,
+    Key::NumTwo,
+    Key::NumThree,
+    Key::NumFour,
+    Key::NumFive,
+    Key::NumSix,
+    Key::NumSeven,
+    Key::NumEight,
+    Key::NumNine,
+    Key::NumZero,
+    Key::NumPeriod,
+    Key::NumSlash,
+    Key::NumStar,
+    Key::NumMinus,
+    Key::NumPlus,
+    Key::NumEqual,
+    Key::NumComma,
+];
+
+#[test]
+fn evdev_round_trip() {
+    for &key in ALL {
+        match key.to_evdev() {
+            Some(code) => assert_eq!(Key::from_evdev(code), Some(key)),
+            None => assert!(matches!(key, Key::Thumb), "{:?}", key),
+        }
+    }
+}
+
+#[test]
+fn evdev_codes() {
+    for code in 0..=0x2FF {
+        if let Some(key) = Key::from_evdev(code) {
+            assert!(key.to_evdev().is_some(), "{}", code);
+        }
+    }
+    assert_eq!(Key::from_evdev(30), Some(Key::A));
+    assert_eq!(Key::from_evdev(14), Some(Key::Delete));
+    assert_eq!(Key::from_evdev(111), Some(Key::Del));
+    assert_eq!(Key::from_evdev(79), Some(Key::NumOne));
+    assert_eq!(Key::One.to_evdev(), Some(2));
+    assert_eq!(Key::from_evdev(55), Some(Key::NumStar));
+    assert_eq!(Key::from_evdev(0), None);
+}
+
+#[test]
+fn hid_round_trip() {
+    for &key in ALL {
+        match key.to_hid_usage() {
+            Some((page, id)) => {
+                assert_eq!(Key::from_hid_usage(page, id), Some(key))
+            }
+            None => {
+                assert!(matches!(key, Key::Thumb | Key::Break), "{:?}", key)
+            }
+        }
+    }
+}
+
+#[test]
+fn hid_usages() {
+    for &page in &[0x01, 0x07, 0x0C] {
+        for id in 0..=0x3FF {
+            if let Some(key) = Key::from_hid_usage(page, id) {
+                assert!(key.to_hid_usage().is_some(), "{}:{}", page, id);
+            }
+        }
+    }
+    assert_eq!(Key::from_hid_usage(0x07, 0x04), Some(Key::A));
+    assert_eq!(Key::from_hid_usage(0x07, 0x2A), Some(Key::Delete));
+    assert_eq!(Key::Mute.to_hid_usage(), Some((0x0C, 0xE2)));
+    // Keyboard page volume keys convert, but consumer page is preferred.
+    assert_eq!(Key::from_hid_usage(0x07, 0x7F), Some(Key::Mute));
+    assert_eq!(Key::from_hid_usage(0x07, 0x55), Some(Key::NumStar));
+    assert_eq!(Key::from_hid_usage(0x09, 0x01), None);
+}
+
+#[test]
+fn evdev_matches_hid() {
+    // Keys with both codes should agree with the kernel's HID mapping.
+    let pairs: &[(u16, (u16, u16))] = &[
+        (1, (0x07, 0x29)),
+        (14, (0x07, 0x2A)),
+        (43, (0x07, 0x31)),
+        (99, (0x07, 0x46)),
+        (127, (0x07, 0x65)),
+        (164, (0x0C, 0xCD)),
+        (247, (0x01, 0xC6)),
+    ];
+    for &(code, (page, id)) in pairs {
+        assert_eq!(Key::from_evdev(code), Key::from_hid_usage(page, id));
+    }
+}
+
+#[test]
+fn keysym_round_trip() {
+    for &key in ALL {
+        match key.to_keysym() {
+            Some(keysym) => assert_eq!(Key::from_keysym(keysym), Some(key)),
+            None => panic!("{:?}", key),
+        }
+    }
+}
+
+#[test]
+fn keysyms() {
+    let ranges = [0x0000..=0x00FF, 0xFE00..=0xFFFF, 0x1008_FF00..=0x1008_FFFF];
+    for range in ranges.iter().cloned() {
+        for keysym in range {
+            if let Some(key) = Key::from_keysym(keysym) {
+                assert!(key.to_keysym().is_some(), "{:#x}", keysym);
+            }
+        }
+    }
+    assert_eq!(Key::from_keysym(0x61), Some(Key::A));
+    assert_eq!(Key::from_keysym(0x41), Some(Key::A));
+    assert_eq!(Key::A.to_keysym(), Some(0x61));
+    assert_eq!(Key::from_keysym(0xFF08), Some(Key::Delete));
+    assert_eq!(Key::from_keysym(0xFFFF), Some(Key::Del));
+    assert_eq!(Key::from_keysym(0xFFB1), Some(Key::NumOne));
+    assert_eq!(Key::from_keysym(0xFFAA), Some(Key::NumStar));
+    assert_eq!(Key::from_keysym(0xFF95), Some(Key::NumSeven));
+    assert_eq!(Key::NumSeven.to_keysym(), Some(0xFFB7));
+    assert_eq!(Key::from_keysym(0x21), None);
+}
+
+#[test]
+fn vk_round_trip() {
+    for &key in ALL {
+        match key.to_vk() {
+            Some(vk) => assert_eq!(Key::from_vk(vk), Some(key)),
+            None => assert!(
+                matches!(
+                    key,
+                    Key::Thumb
+                        | Key::Power
+                        | Key::Microphone
+                        | Key::Airplane
+                        | Key::Display
+                        | Key::NumEnter
+                        | Key::Dimmer
+                        | Key::Brighter
+                ),
+                "{:?}",
+                key
+            ),
+        }
+    }
+}
+
+#[test]
+fn vks() {
+    for vk in 0..=0xFF {
+        if let Some(key) = Key::from_vk(vk) {
+            assert!(key.to_vk().is_some(), "{:#x}", vk);
+        }
+    }
+    assert_eq!(Key::from_vk(0x41), Some(Key::A));
+    assert_eq!(Key::from_vk(0x08), Some(Key::Delete));
+    assert_eq!(Key::from_vk(0x10), Some(Key::LShift));
+    assert_eq!(Key::LShift.to_vk(), Some(0xA0));
+    assert_eq!(Key::from_vk(0x61), Some(Key::NumOne));
+    assert_eq!(Key::from_vk(0x6A), Some(Key::NumStar));
+    assert_eq!(Key::from_vk(0x00), None);
+}
+
+#[test]
+fn names_round_trip() {
+    for &key in ALL {
+        let name = key.to_string();
+        assert_eq!(name, format!("{:?}", key));
+        assert_eq!(name.parse::<Key>(), Ok(key));
+        assert_eq!(name.to_lowercase().parse::<Key>(), Ok(key));
+    }
+}
+
+#[test]
+fn names() {
+    assert_eq!("PageUp".parse::<Key>(), Ok(Key::PageUp));
+    assert_eq!("1".parse::<Key>(), Ok(Key::One));
+    assert_eq!("p".parse::<Key>(), Ok(Key::P));
+    assert_eq!("/".parse::<Key>(), Ok(Key::Slash));
+    assert_eq!(
+        "Shift".parse::<Key>(),
+        Err(ParseError::UnknownKey("Shift".to_string()))
+    );
+    assert_eq!("!".parse::<Key>().ok(), None);
+    assert_eq!("".parse::<Key>(), Err(ParseError::Empty));
+}
+
+#[test]
+fn dom_round_trip() {
+    for &key in ALL {
+        match key.to_dom_code() {
+            Some(code) => assert_eq!(Key::from_dom_code(code), Some(key)),
+            // The keys documented on `Key::from_dom_code()` as having no code.
+            None => assert!(
+                matches!(key, Key::Thumb | Key::Break | Key::Airplane),
+                "{:?}",
+                key
+            ),
+        }
+    }
+}
+
+#[test]
+fn dom_codes() {
+    assert_eq!(Key::from_dom_code("Backspace"), Some(Key::Delete));
+    assert_eq!(Key::from_dom_code("Delete"), Some(Key::Del));
+    assert_eq!(Key::from_dom_code("Escape"), Some(Key::Esc));
+    assert_eq!(Key::from_dom_code("NumLock"), Some(Key::Num));
+    assert_eq!(Key::from_dom_code("ScrollLock"), Some(Key::Scroll));
+    assert_eq!(Key::from_dom_code("IntlBackslash"), Some(Key::Backslash));
+    assert_eq!(Key::from_dom_code("IntlRo"), Some(Key::Backslash));
+    assert_eq!(Key::from_dom_code("Lang1"), Some(Key::Han));
+    assert_eq!(Key::from_dom_code("Numpad7"), Some(Key::NumSeven));
+    assert_eq!(Key::from_dom_code("NumpadEnter"), Some(Key::NumEnter));
+    assert_eq!(Key::from_dom_code("VolumeMute"), Some(Key::Mute));
+    assert_eq!(Key::Seven.to_dom_code(), Some("Digit7"));
+    assert_eq!(Key::from_dom_code("NumpadMultiply"), Some(Key::NumStar));
+    assert_eq!(Key::from_dom_code("MetaLeft"), None);
+    assert_eq!(Key::from_dom_code(""), None);
+}
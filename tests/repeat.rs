@@ -0,0 +1,75 @@
+//! Software key repeat.
+
+use std::time::Duration;
+
+use human::{
+    Event, Input, Key,
+    KeyAction::{Press, Release, Repeat},
+    KeyRepeat, Mod,
+};
+
+const DELAY: Duration = Duration::from_millis(500);
+
+fn at(millis: u64, input: Input) -> Event {
+    Event::new(Duration::from_millis(millis), input)
+}
+
+fn millis(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+#[test]
+fn repeats() {
+    let mut repeat = KeyRepeat::new(DELAY, 4.0);
+    assert_eq!(repeat.deadline(), None);
+
+    let press = at(1000, Input::Key(Mod::new(), Key::A, Press));
+    assert!(repeat.filter(press).is_some());
+    assert_eq!(repeat.deadline(), Some(millis(1500)));
+    assert!(repeat.repeat(millis(1499)).is_none());
+
+    let event = repeat.repeat(millis(1500)).unwrap();
+    assert_eq!(event.time(), millis(1500));
+    assert!(matches!(event.input(), Input::Key(m, Key::A, Repeat) if m.none()));
+    assert_eq!(repeat.deadline(), Some(millis(1750)));
+
+    // Missed repeats are skipped.
+    assert!(repeat.repeat(millis(2300)).is_some());
+    assert!(repeat.repeat(millis(2300)).is_none());
+    assert_eq!(repeat.deadline(), Some(millis(2550)));
+
+    let release = at(2400, Input::Key(Mod::new(), Key::A, Release));
+    assert!(repeat.filter(release).is_some());
+    assert_eq!(repeat.deadline(), None);
+    assert!(repeat.repeat(millis(3000)).is_none());
+}
+
+#[test]
+fn os_repeats_dropped() {
+    let mut repeat = KeyRepeat::new(DELAY, 4.0);
+    let os_repeat = at(0, Input::Key(Mod::new(), Key::A, Repeat));
+    assert!(repeat.filter(os_repeat).is_none());
+}
+
+#[test]
+fn modifiers() {
+    let mut repeat = KeyRepeat::new(DELAY, 4.0);
+    let shift = Mod::new().add_shift();
+
+    repeat.filter(at(0, Input::Key(shift, Key::LShift, Press)));
+    assert_eq!(repeat.deadline(), None);
+
+    repeat.filter(at(0, Input::Key(Mod::new(), Key::A, Press)));
+    repeat.filter(at(100, Input::Key(shift, Key::LShift, Press)));
+    let event = repeat.repeat(DELAY).unwrap();
+    assert!(
+        matches!(event.input(), Input::Key(m, Key::A, Repeat) if m.shift())
+    );
+
+    // Releasing another key doesn't stop the repeat.
+    repeat.filter(at(600, Input::Key(Mod::new(), Key::LShift, Release)));
+    assert_eq!(repeat.deadline(), Some(millis(750)));
+
+    repeat.filter(at(600, Input::FocusLost));
+    assert_eq!(repeat.deadline(), None);
+}
@@ -0,0 +1,171 @@
+//! Displaying and parsing of modifiers, buttons and shortcuts.
+
+use human::{
+    Btn, Input, Key,
+    KeyAction::{Press, Release},
+    Mod, ParseError, Shortcut,
+};
+
+#[test]
+fn mods() {
+    let all = Mod::new().add_shift().add_alt().add_ctrl();
+    assert_eq!(all.to_string(), "Ctrl+Alt+Shift");
+    assert_eq!(Mod::new().to_string(), "None");
+    assert_eq!(Mod::new().add_shift().to_string(), "Shift");
+
+    assert_eq!("Ctrl+Alt+Shift".parse(), Ok(all));
+    assert_eq!("shift + ctrl + alt".parse(), Ok(all));
+    assert_eq!("None".parse(), Ok(Mod::new()));
+    assert_eq!(
+        "Ctrl+Hyper".parse::<Mod>(),
+        Err(ParseError::UnknownMod("Hyper".to_string()))
+    );
+    assert_eq!("Ctrl+".parse::<Mod>(), Err(ParseError::Empty));
+}
+
+#[test]
+fn mods_round_trip() {
+    for bits in 0..8 {
+        let mut mods = Mod::new();
+        if bits & 1 != 0 {
+            mods = mods.add_shift();
+        }
+        if bits & 2 != 0 {
+            mods = mods.add_ctrl();
+        }
+        if bits & 4 != 0 {
+            mods = mods.add_alt();
+        }
+        assert_eq!(mods.to_string().parse(), Ok(mods));
+    }
+}
+
+#[test]
+fn btns() {
+    let all = [
+        Btn::Left,
+        Btn::Middle,
+        Btn::Right,
+        Btn::Back,
+        Btn::Next,
+        Btn::Dpi,
+        Btn::Extra,
+    ];
+    for &btn in all.iter() {
+        assert_eq!(btn.to_string(), format!("{:?}", btn));
+        assert_eq!(btn.to_string().parse(), Ok(btn));
+    }
+    assert_eq!(
+        "Forward".parse::<Btn>(),
+        Err(ParseError::UnknownBtn("Forward".to_string()))
+    );
+}
+
+#[test]
+fn shortcuts() {
+    let ctrl_shift = Mod::new().add_ctrl().add_shift();
+    let palette = Shortcut::Key(ctrl_shift, Key::P);
+    let pan = Shortcut::Btn(Mod::new().add_alt(), Btn::Middle);
+    let escape = Shortcut::Key(Mod::new(), Key::Esc);
+
+    assert_eq!(palette.to_string(), "Ctrl+Shift+P");
+    assert_eq!(pan.to_string(), "Alt+Btn:Middle");
+    assert_eq!(escape.to_string(), "Esc");
+
+    for shortcut in [palette, pan, escape].iter() {
+        assert_eq!(shortcut.to_string().parse(), Ok(*shortcut));
+    }
+    assert_eq!("shift+ctrl+p".parse(), Ok(palette));
+    assert_eq!("Ctrl + Shift + P".parse(), Ok(palette));
+    assert_eq!("alt+btn:middle".parse(), Ok(pan));
+    assert_eq!(
+        "Ctrl+=".parse(),
+        Ok(Shortcut::Key(Mod::new().add_ctrl(), Key::Equal))
+    );
+}
+
+#[test]
+fn shortcut_errors() {
+    assert_eq!("".parse::<Shortcut>(), Err(ParseError::Empty));
+    assert_eq!("Ctrl+".parse::<Shortcut>(), Err(ParseError::Empty));
+    assert_eq!(
+        "Ctrl+Shift".parse::<Shortcut>(),
+        Err(ParseError::MissingKey)
+    );
+    assert_eq!(
+        "Ctrl+Shft+P".parse::<Shortcut>(),
+        Err(ParseError::UnknownMod("Shft".to_string()))
+    );
+    assert_eq!(
+        "Ctrl+Pgup".parse::<Shortcut>(),
+        Err(ParseError::UnknownKey("Pgup".to_string()))
+    );
+    assert_eq!(
+        "Alt+Btn:Wheel".parse::<Shortcut>(),
+        Err(ParseError::UnknownBtn("Wheel".to_string()))
+    );
+    assert_eq!(
+        ParseError::UnknownKey("Pgup".to_string()).to_string(),
+        "unknown key `Pgup`"
+    );
+}
+
+#[test]
+fn matches() {
+    let palette: Shortcut = "Ctrl+Shift+P".parse().unwrap();
+    let mods = Mod::new().add_ctrl().add_shift();
+
+    assert!(palette.matches(&Input::Key(mods, Key::P, Press)));
+    assert!(!palette.matches(&Input::Key(mods, Key::P, Release)));
+    assert!(!palette.matches(&Input::Key(
+        Mod::new().add_ctrl(),
+        Key::P,
+        Press
+    )));
+    assert!(!palette.matches(&Input::Click(mods, Btn::Left, true)));
+}
+
+#[test]
+fn extended_mods() {
+    let mods = Mod::new()
+        .add_super()
+        .add_altgr()
+        .add_caps_lock()
+        .add_num_lock()
+        .add_scroll_lock();
+    assert_eq!(mods.to_string(), "Super+AltGr+CapsLock+NumLock+ScrollLock");
+    assert_eq!(
+        format!("{:?}", mods),
+        "Super + AltGr + CapsLock + NumLock + ScrollLock"
+    );
+    assert_eq!(mods.to_string().parse(), Ok(mods));
+    assert_eq!(
+        "Meta+C".parse(),
+        Ok(Shortcut::Key(Mod::new().add_super(), Key::C))
+    );
+
+    let locks = Mod::new().add_caps_lock().add_num_lock();
+    assert!(locks.none());
+    assert_eq!(locks.held(), Mod::new());
+    assert_eq!(
+        Mod::new().add_ctrl().add_num_lock().held(),
+        Mod::new().add_ctrl()
+    );
+    assert_eq!("CapsLock".parse::<Shortcut>(), Err(ParseError::MissingKey));
+}
+
+#[test]
+fn matches_with_locks() {
+    let copy: Shortcut = "Ctrl+C".parse().unwrap();
+    let num_lock = Mod::new().add_ctrl().add_num_lock();
+    assert!(copy.matches(&Input::Key(num_lock, Key::C, Press)));
+    assert!(!copy.matches(&Input::Key(Mod::new().add_super(), Key::C, Press)));
+
+    let caps: Shortcut = "CapsLock+A".parse().unwrap();
+    assert!(caps.matches(&Input::Key(
+        Mod::new().add_caps_lock(),
+        Key::A,
+        Press
+    )));
+    assert!(!caps.matches(&Input::Key(Mod::new(), Key::A, Press)));
+}
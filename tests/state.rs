@@ -0,0 +1,75 @@
+//! Keyboard and pointer state tracking.
+
+use human::{
+    Btn, Input, Key,
+    KeyAction::{Press, Release, Repeat},
+    KeyboardState, Mod, PointerState,
+};
+
+#[test]
+fn keyboard() {
+    let mut state = KeyboardState::new();
+    let shift = Mod::new().add_shift();
+    state.update(&Input::Key(shift, Key::LShift, Press));
+    state.update(&Input::Key(shift, Key::A, Press));
+    state.update(&Input::Key(Mod::new(), Key::B, Press));
+    state.update(&Input::Key(Mod::new(), Key::B, Release));
+
+    assert!(state.is_pressed(Key::LShift));
+    assert!(state.is_pressed(Key::A));
+    assert!(!state.is_pressed(Key::B));
+    assert!(state.just_pressed(Key::B));
+    assert!(state.just_released(Key::B));
+    assert!(state.mods().none());
+    assert_eq!(
+        state.pressed_keys().collect::<Vec<_>>(),
+        [Key::A, Key::LShift]
+    );
+
+    state.next_frame();
+    state.update(&Input::Key(shift, Key::A, Release));
+
+    assert!(!state.just_pressed(Key::A));
+    assert!(!state.just_pressed(Key::B));
+    assert!(state.just_released(Key::A));
+    assert!(!state.just_released(Key::B));
+    assert!(state.mods().shift());
+    assert_eq!(state.pressed_keys().collect::<Vec<_>>(), [Key::LShift]);
+}
+
+#[test]
+fn repeats() {
+    let mut state = KeyboardState::new();
+    state.update(&Input::Key(Mod::new(), Key::A, Press));
+    state.next_frame();
+    state.update(&Input::Key(Mod::new(), Key::A, Repeat));
+
+    assert!(state.is_pressed(Key::A));
+    assert!(!state.just_pressed(Key::A));
+}
+
+#[test]
+fn pointer() {
+    let mut state = PointerState::new();
+    assert_eq!(state.position(), None);
+
+    state.update(&Input::PointerMove { x: 0.25, y: 0.5 });
+    state.update(&Input::Click(Mod::new().add_ctrl(), Btn::Left, true));
+
+    assert_eq!(state.position(), Some((0.25, 0.5)));
+    assert!(state.is_pressed(Btn::Left));
+    assert!(state.just_pressed(Btn::Left));
+    assert!(!state.is_pressed(Btn::Right));
+    assert!(state.mods().ctrl());
+    assert_eq!(state.pressed_buttons().collect::<Vec<_>>(), [Btn::Left]);
+
+    state.next_frame();
+    state.update(&Input::Click(Mod::new(), Btn::Left, false));
+    state.update(&Input::PointerLeave);
+
+    assert!(!state.is_pressed(Btn::Left));
+    assert!(!state.just_pressed(Btn::Left));
+    assert!(state.just_released(Btn::Left));
+    assert_eq!(state.position(), None);
+    assert_eq!(state.pressed_buttons().count(), 0);
+}
@@ -0,0 +1,286 @@
+//! Parsing of recorded terminal input.
+
+use human::{
+    Btn, Input, Key,
+    KeyAction::{Press, Release, Repeat},
+    TermParser,
+};
+
+fn parse(bytes: &[u8]) -> Vec<Input> {
+    let mut parser = TermParser::new();
+    parser.push(bytes);
+    parser.flush();
+    std::iter::from_fn(|| parser.pop()).collect()
+}
+
+#[test]
+fn text() {
+    let mut parser = TermParser::new();
+    for c in &["h", "I", "é"] {
+        parser.push(c.as_bytes());
+    }
+    let inputs: Vec<Input> = std::iter::from_fn(|| parser.pop()).collect();
+
+    assert_eq!(inputs.len(), 7);
+    assert!(matches!(inputs[0], Input::Key(m, Key::H, Press) if m.none()));
+    assert!(matches!(inputs[1], Input::Text(ref t) if t == "h"));
+    assert!(matches!(inputs[2], Input::Key(m, Key::H, Release) if m.none()));
+    assert!(matches!(inputs[3], Input::Key(m, Key::I, Press) if m.shift()));
+    assert!(matches!(inputs[4], Input::Text(ref t) if t == "I"));
+    assert!(matches!(inputs[6], Input::Text(ref t) if t == "é"));
+}
+
+#[test]
+fn text_burst() {
+    // Several characters in one read, like text committed by an IME.
+    let inputs = parse("hI 日本\r".as_bytes());
+
+    assert_eq!(inputs.len(), 9);
+    assert!(matches!(inputs[0], Input::Key(_, Key::H, Press)));
+    assert!(matches!(inputs[1], Input::Key(_, Key::H, Release)));
+    assert!(matches!(inputs[5], Input::Key(_, Key::Space, Release)));
+    assert!(matches!(inputs[6], Input::Text(ref t) if t == "hI 日本"));
+    assert!(matches!(inputs[7], Input::Key(_, Key::Enter, Press)));
+}
+
+#[test]
+fn control_keys() {
+    let inputs = parse(b"\x03\r\t\x7f");
+
+    assert_eq!(inputs.len(), 8);
+    assert!(matches!(inputs[0], Input::Key(m, Key::C, Press) if m.ctrl()));
+    assert!(matches!(inputs[2], Input::Key(m, Key::Enter, Press) if m.none()));
+    assert!(matches!(inputs[4], Input::Key(_, Key::Tab, Press)));
+    assert!(matches!(inputs[6], Input::Key(_, Key::Delete, Press)));
+}
+
+#[test]
+fn escape_sequences() {
+    let inputs = parse(b"\x1b[A\x1b[1;5C\x1bOP\x1b[3~\x1b[15;2~\x1b[Z\x1bx");
+
+    assert_eq!(inputs.len(), 14);
+    assert!(matches!(inputs[0], Input::Key(m, Key::Up, Press) if m.none()));
+    assert!(matches!(inputs[2], Input::Key(m, Key::Right, Press) if m.ctrl()));
+    assert!(matches!(inputs[4], Input::Key(_, Key::F1, Press)));
+    assert!(matches!(inputs[6], Input::Key(_, Key::Del, Press)));
+    assert!(matches!(inputs[8], Input::Key(m, Key::F5, Press) if m.shift()));
+    assert!(matches!(inputs[10], Input::Key(m, Key::Tab, Press) if m.shift()));
+    assert!(matches!(inputs[12], Input::Key(m, Key::X, Press) if m.alt()));
+}
+
+#[test]
+fn lone_escape() {
+    let mut parser = TermParser::new();
+    parser.push(b"\x1b");
+    assert!(parser.pop().is_none());
+    assert!(parser.pending());
+    parser.flush();
+    assert!(!parser.pending());
+    assert!(matches!(parser.pop(), Some(Input::Key(_, Key::Esc, Press))));
+    assert!(matches!(
+        parser.pop(),
+        Some(Input::Key(_, Key::Esc, Release))
+    ));
+    assert!(parser.pop().is_none());
+}
+
+#[test]
+fn split_sequence() {
+    let mut parser = TermParser::new();
+    parser.push(b"\x1b[1;");
+    parser.push(b"2B");
+    assert!(
+        matches!(parser.pop(), Some(Input::Key(m, Key::Down, Press)) if m.shift())
+    );
+}
+
+#[test]
+fn sgr_mouse() {
+    let mut parser = TermParser::new();
+    parser.resize(100, 50);
+    parser.push(b"\x1b[<0;11;26M\x1b[<0;11;26m\x1b[<35;21;26M\x1b[<65;21;26M");
+    let inputs: Vec<Input> = std::iter::from_fn(|| parser.pop()).collect();
+
+    assert_eq!(inputs.len(), 5);
+    assert!(matches!(
+        inputs[0],
+        Input::PointerMove { x, y } if x == 0.105 && y == 0.51
+    ));
+    assert!(matches!(inputs[1], Input::Click(m, Btn::Left, true) if m.none()));
+    assert!(matches!(inputs[2], Input::Click(_, Btn::Left, false)));
+    assert!(matches!(
+        inputs[3],
+        Input::PointerMove { x, y } if x == 0.205 && y == 0.51
+    ));
+    assert!(matches!(
+        inputs[4],
+        Input::Scroll(_, s)
+            if s.detents() == (0, 1) && s.delta() == (0.0, 48.0)
+    ));
+}
+
+#[test]
+fn bracketed_paste() {
+    let inputs = parse(b"\x1b[200~a\x1b[Bb\x1b[201~c");
+
+    assert_eq!(inputs.len(), 4);
+    assert!(matches!(inputs[0], Input::Paste(ref t) if t == "a\x1b[Bb"));
+    assert!(matches!(inputs[1], Input::Key(_, Key::C, Press)));
+    assert!(matches!(inputs[2], Input::Text(ref t) if t == "c"));
+}
+
+#[test]
+fn clipboard_reply() {
+    let mut parser = TermParser::new();
+    parser.push(b"\x1b]52;c;aGVsbG8=\x07");
+    assert_eq!(parser.clipboard().as_deref(), Some("hello"));
+    assert!(parser.pop().is_none());
+
+    // Terminated by ST, and split across reads.
+    parser.push(b"\x1b]52;c;4pyTIGRv");
+    parser.flush();
+    assert_eq!(parser.clipboard(), None);
+    parser.push(b"bmU=\x1b\\a");
+    assert_eq!(parser.clipboard().as_deref(), Some("✓ done"));
+    assert!(matches!(parser.pop(), Some(Input::Key(_, Key::A, Press))));
+
+    // Alt + ]
+    let inputs = parse(b"\x1b]");
+    assert_eq!(inputs.len(), 2);
+    assert!(
+        matches!(inputs[0], Input::Key(m, Key::BracketClose, Press) if m.alt())
+    );
+}
+
+#[test]
+fn focus() {
+    let inputs = parse(b"\x1b[O\x1b[I");
+
+    assert_eq!(inputs.len(), 2);
+    assert!(matches!(inputs[0], Input::FocusLost));
+    assert!(matches!(inputs[1], Input::FocusGained));
+}
+
+#[test]
+fn kitty_negotiation() {
+    let mut parser = TermParser::new();
+    assert_eq!(parser.kitty(), None);
+    parser.push(b"\x1b[?0u\x1b[?62;22c");
+    assert_eq!(parser.kitty(), Some(true));
+    assert!(parser.pop().is_none());
+
+    let mut parser = TermParser::new();
+    parser.push(b"\x1b[?62;22c");
+    assert_eq!(parser.kitty(), Some(false));
+}
+
+#[test]
+fn kitty_keys() {
+    let mut parser = TermParser::new();
+    parser.set_enhanced(true);
+    // Left shift, A (with text), A repeat, A release, left shift release.
+    parser.push(b"\x1b[57441;2u\x1b[97:65;2;65u\x1b[97:65;2:2;65u");
+    parser.push(b"\x1b[97:65;2:3u\x1b[57441;1:3u");
+    // Right shift, ctrl + i, tab, up arrow release.
+    parser.push(b"\x1b[57447;2u\x1b[105;5u\x1b[9u\x1b[1;1:3A");
+    let inputs: Vec<Input> = std::iter::from_fn(|| parser.pop()).collect();
+
+    assert_eq!(inputs.len(), 11);
+    assert!(
+        matches!(inputs[0], Input::Key(m, Key::LShift, Press) if m.shift())
+    );
+    assert!(matches!(inputs[1], Input::Key(m, Key::A, Press) if m.shift()));
+    assert!(matches!(inputs[2], Input::Text(ref t) if t == "A"));
+    assert!(matches!(inputs[3], Input::Key(m, Key::A, Repeat) if m.shift()));
+    assert!(matches!(inputs[4], Input::Text(ref t) if t == "A"));
+    assert!(matches!(inputs[5], Input::Key(m, Key::A, Release) if m.shift()));
+    assert!(
+        matches!(inputs[6], Input::Key(m, Key::LShift, Release) if m.none())
+    );
+    assert!(matches!(inputs[7], Input::Key(_, Key::RShift, Press)));
+    assert!(matches!(inputs[8], Input::Key(m, Key::I, Press) if m.ctrl()));
+    assert!(matches!(inputs[9], Input::Key(m, Key::Tab, Press) if m.none()));
+    assert!(matches!(inputs[10], Input::Key(_, Key::Up, Release)));
+}
+
+#[test]
+fn kitty_text() {
+    let mut parser = TermParser::new();
+    parser.set_enhanced(true);
+    // E followed by a combining acute accent, typed with one key.
+    parser.push(b"\x1b[101;1;101:769u");
+    let inputs: Vec<Input> = std::iter::from_fn(|| parser.pop()).collect();
+
+    assert_eq!(inputs.len(), 2);
+    assert!(matches!(inputs[0], Input::Key(_, Key::E, Press)));
+    assert!(matches!(inputs[1], Input::Text(ref t) if t == "e\u{301}"));
+}
+
+#[test]
+fn kitty_fallback() {
+    // Without enhancements, CSI u keys are still understood.
+    let inputs = parse(b"\x1b[105;5u");
+
+    assert_eq!(inputs.len(), 2);
+    assert!(matches!(inputs[0], Input::Key(m, Key::I, Press) if m.ctrl()));
+    assert!(matches!(inputs[1], Input::Key(m, Key::I, Release) if m.ctrl()));
+}
+
+#[test]
+fn kitty_modifiers() {
+    let mut parser = TermParser::new();
+    parser.set_enhanced(true);
+    // Super + c, ctrl + c, a with caps lock on.
+    parser.push(b"\x1b[99;9u\x1b[99;5u\x1b[97;65u");
+    // AltGr + q, then q after AltGr is released.
+    parser.push(b"\x1b[57453u\x1b[113u\x1b[57453;1:3u\x1b[113u");
+    let inputs: Vec<Input> = std::iter::from_fn(|| parser.pop()).collect();
+
+    assert_eq!(inputs.len(), 10);
+    assert!(
+        matches!(inputs[0], Input::Key(m, Key::C, Press) if m.super_key() && !m.ctrl())
+    );
+    assert!(
+        matches!(inputs[1], Input::Key(m, Key::C, Press) if m.ctrl() && !m.super_key())
+    );
+    assert!(
+        matches!(inputs[2], Input::Key(m, Key::A, Press) if m.caps_lock() && m.none())
+    );
+    assert!(matches!(inputs[4], Input::Key(m, Key::Thumb, Press) if m.altgr()));
+    assert!(matches!(inputs[5], Input::Key(m, Key::Q, Press) if m.altgr()));
+    assert!(
+        matches!(inputs[7], Input::Key(m, Key::Thumb, Release) if m.none())
+    );
+    assert!(matches!(inputs[8], Input::Key(m, Key::Q, Press) if m.none()));
+}
+
+#[test]
+fn numpad() {
+    let mut parser = TermParser::new();
+    parser.set_enhanced(true);
+    // Keypad 1, keypad *, keypad home (7 with Num Lock off).
+    parser.push(b"\x1b[57400u\x1b[57411u\x1b[57423u");
+    let inputs: Vec<Input> = std::iter::from_fn(|| parser.pop()).collect();
+
+    assert_eq!(inputs.len(), 3);
+    assert!(matches!(inputs[0], Input::Key(_, Key::NumOne, Press)));
+    assert!(
+        matches!(inputs[1], Input::Key(m, Key::NumStar, Press) if m.none())
+    );
+    assert!(matches!(inputs[2], Input::Key(_, Key::NumSeven, Press)));
+
+    // Application keypad mode.
+    let inputs = parse(b"\x1bOq\x1bOk\x1bOM");
+
+    assert_eq!(inputs.len(), 6);
+    assert!(matches!(inputs[0], Input::Key(_, Key::NumOne, Press)));
+    assert!(matches!(inputs[2], Input::Key(_, Key::NumPlus, Press)));
+    assert!(matches!(inputs[4], Input::Key(_, Key::NumEnter, Press)));
+
+    // Keypad begin (5 with Num Lock off), in normal and application mode.
+    let inputs = parse(b"\x1b[E\x1bOE");
+
+    assert_eq!(inputs.len(), 4);
+    assert!(matches!(inputs[0], Input::Key(_, Key::NumFive, Press)));
+    assert!(matches!(inputs[2], Input::Key(_, Key::NumFive, Press)));
+}